@@ -0,0 +1,315 @@
+/// Sub-language injection: some String/Comment tokens embed another
+/// language's source (a Bash heredoc body, a `` `cmd` ``/`$(cmd)` command
+/// substitution inside a non-Bash string, a fenced code block with a
+/// `language-xxx`-style info string inside a string or comment). This pass
+/// runs after the host tokenizer and recursively re-tokenizes those inner
+/// substrings, splicing the translated inner tokens in place of the flat
+/// host span so `generate_html_with_classes` renders them highlighted too.
+use crate::tokenizer::{Language, Token, TokenType, Tokenizer};
+
+/// Recursion guard: a pathological input (or a language injecting itself)
+/// can't nest deeper than this before injection just stops looking.
+const MAX_INJECTION_DEPTH: usize = 4;
+
+/// Tokenize `code` as `language` and expand any injectable String/Comment
+/// tokens into their inner, recursively-tokenized spans.
+pub fn highlight_with_injection(code: &str, language: Language) -> Vec<Token> {
+    highlight_with_injection_and_comment_prefix(code, language, "#")
+}
+
+/// Like `highlight_with_injection`, but overrides the line-comment prefix
+/// used when `language` is `Language::Generic` (ignored otherwise): callers
+/// that resolved a specific interpreter (Node vs. Python/Ruby/Perl) can
+/// thread its comment syntax through instead of settling for the default.
+pub fn highlight_with_injection_and_comment_prefix(
+    code: &str,
+    language: Language,
+    generic_comment_prefix: &'static str,
+) -> Vec<Token> {
+    let mut tokenizer = Tokenizer::new(code, language);
+    tokenizer.set_line_comment_prefix(generic_comment_prefix);
+    let tokens = tokenizer.tokenize();
+    expand_tokens(code, language, tokens, 0)
+}
+
+fn expand_tokens(code: &str, host_language: Language, tokens: Vec<Token>, depth: usize) -> Vec<Token> {
+    if depth >= MAX_INJECTION_DEPTH {
+        return tokens;
+    }
+
+    let mut expanded = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        let is_injectable = matches!(token.token_type, TokenType::String | TokenType::Comment);
+        if is_injectable {
+            let text = &code[token.start..token.end];
+            if let Some(spans) = injected_spans(host_language, &token, text, depth) {
+                expanded.extend(spans);
+                continue;
+            }
+        }
+        expanded.push(token);
+    }
+    expanded
+}
+
+/// Detect and expand one injection site inside `token`'s text, if any.
+/// Returns `None` (leaving the flat host span in place) when nothing is
+/// recognized or the candidate inner text is empty/whitespace-only.
+fn injected_spans(
+    host_language: Language,
+    token: &Token,
+    text: &str,
+    depth: usize,
+) -> Option<Vec<Token>> {
+    if text.trim().is_empty() {
+        return None;
+    }
+
+    if host_language == Language::Bash
+        && token.token_type == TokenType::String
+        && text.starts_with("<<")
+    {
+        if let Some(spans) = inject_heredoc_body(token, text, depth) {
+            return Some(spans);
+        }
+    }
+
+    if host_language != Language::Bash {
+        if let Some((start, end)) = find_command_substitution(text) {
+            return Some(inject_range(token, text, start, end, Language::Bash, depth));
+        }
+    }
+
+    if let Some((lang, start, end)) = find_fenced_code_block(text) {
+        return Some(inject_range(token, text, start, end, lang, depth));
+    }
+
+    None
+}
+
+/// Re-tokenize `text[inner_start..inner_end]` as `lang`, translate the
+/// resulting offsets into `code`'s coordinate space (`token.start` +
+/// position within `text`), and keep the untouched prefix/suffix of `text`
+/// as plain spans of the host token's original type.
+fn inject_range(
+    token: &Token,
+    text: &str,
+    inner_start: usize,
+    inner_end: usize,
+    lang: Language,
+    depth: usize,
+) -> Vec<Token> {
+    let mut spans = Vec::new();
+
+    if inner_start > 0 {
+        spans.push(Token {
+            token_type: token.token_type.clone(),
+            start: token.start,
+            end: token.start + inner_start,
+        });
+    }
+
+    let inner_text = &text[inner_start..inner_end];
+    let inner_tokens = Tokenizer::new(inner_text, lang).tokenize();
+    for mut inner in expand_tokens(inner_text, lang, inner_tokens, depth + 1) {
+        inner.start += token.start + inner_start;
+        inner.end += token.start + inner_start;
+        spans.push(inner);
+    }
+
+    if inner_end < text.len() {
+        spans.push(Token {
+            token_type: token.token_type.clone(),
+            start: token.start + inner_end,
+            end: token.end,
+        });
+    }
+
+    spans
+}
+
+/// Find the first `$(...)` or `` `...` `` command substitution in `text`,
+/// returning the byte range of its *inner* contents (delimiters excluded).
+fn find_command_substitution(text: &str) -> Option<(usize, usize)> {
+    let dollar_paren = text.find("$(").and_then(|start| {
+        let inner_start = start + 2;
+        text[inner_start..]
+            .find(')')
+            .map(|rel| (inner_start, inner_start + rel))
+    });
+    let backtick = text.find('`').and_then(|start| {
+        let inner_start = start + 1;
+        text[inner_start..]
+            .find('`')
+            .map(|rel| (inner_start, inner_start + rel))
+    });
+
+    let candidate = match (dollar_paren, backtick) {
+        (Some(a), Some(b)) => Some(if a.0 <= b.0 { a } else { b }),
+        (a, None) => a,
+        (None, b) => b,
+    }?;
+
+    if candidate.1 > candidate.0 && !text[candidate.0..candidate.1].trim().is_empty() {
+        Some(candidate)
+    } else {
+        None
+    }
+}
+
+/// Find a Markdown-style fenced code block (` ```lang\n...\n``` `) whose
+/// info string names a language we support, returning the language and the
+/// byte range of the fenced body (fences and info string excluded).
+fn find_fenced_code_block(text: &str) -> Option<(Language, usize, usize)> {
+    let fence_start = text.find("```")?;
+    let after_open = fence_start + 3;
+    let info_end = after_open + text[after_open..].find('\n')?;
+    let lang = language_from_name(text[after_open..info_end].trim())?;
+
+    let body_start = info_end + 1;
+    let body_end = body_start + text[body_start..].find("```")?;
+
+    if text[body_start..body_end].trim().is_empty() {
+        return None;
+    }
+
+    Some((lang, body_start, body_end))
+}
+
+/// Map a fenced-block info string (or similar language name) to a `Language`.
+fn language_from_name(name: &str) -> Option<Language> {
+    match name.to_lowercase().as_str() {
+        "c" | "cpp" | "c++" => Some(Language::C),
+        "bash" | "sh" | "shell" | "zsh" => Some(Language::Bash),
+        "make" | "makefile" => Some(Language::Makefile),
+        "yaml" | "yml" => Some(Language::Yaml),
+        _ => None,
+    }
+}
+
+/// The delimiter word of a heredoc marker line (`<<EOF`, `<<-'EOF'`, ...),
+/// re-parsed from plain text since injection only sees token text, not the
+/// tokenizer's internal state.
+fn parse_heredoc_delimiter(marker_line: &str) -> Option<&str> {
+    let rest = marker_line.trim_start().strip_prefix("<<")?;
+    let rest = rest.strip_prefix('-').unwrap_or(rest).trim_start();
+    let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'');
+    let rest = if quote.is_some() { &rest[1..] } else { rest };
+
+    let end = rest
+        .find(|c: char| match quote {
+            Some(q) => c == q,
+            None => c.is_whitespace() || c == ';' || c == '|' || c == '&',
+        })
+        .unwrap_or(rest.len());
+
+    let delimiter = &rest[..end];
+    if delimiter.is_empty() {
+        None
+    } else {
+        Some(delimiter)
+    }
+}
+
+/// Extract the body of a heredoc token's text (everything between the
+/// marker line and the line exactly matching the delimiter), to be
+/// re-tokenized as Bash.
+fn inject_heredoc_body(token: &Token, text: &str, depth: usize) -> Option<Vec<Token>> {
+    let marker_line_end = text.find('\n')? + 1;
+    let delimiter = parse_heredoc_delimiter(&text[..marker_line_end])?;
+    let body = &text[marker_line_end..];
+
+    let mut offset = 0;
+    let mut body_end = body.len();
+    loop {
+        let line_end = body[offset..]
+            .find('\n')
+            .map(|rel| offset + rel + 1)
+            .unwrap_or(body.len());
+        let line = body[offset..line_end].trim_end_matches('\n');
+        if line.trim_start_matches('\t') == delimiter {
+            body_end = offset;
+            break;
+        }
+        if line_end == body.len() {
+            break;
+        }
+        offset = line_end;
+    }
+
+    if body[..body_end].trim().is_empty() {
+        return None;
+    }
+
+    Some(inject_range(
+        token,
+        text,
+        marker_line_end,
+        marker_line_end + body_end,
+        Language::Bash,
+        depth,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token_texts<'a>(code: &'a str, tokens: &[Token]) -> Vec<(&'a str, TokenType)> {
+        tokens
+            .iter()
+            .map(|t| (&code[t.start..t.end], t.token_type.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_heredoc_body_is_highlighted_as_bash() {
+        let code = "cat <<EOF\necho \"$HOME\"\nEOF\n";
+        let tokens = highlight_with_injection(code, Language::Bash);
+
+        assert!(tokens
+            .iter()
+            .any(|t| t.token_type == TokenType::Keyword && &code[t.start..t.end] == "echo"));
+    }
+
+    #[test]
+    fn test_command_substitution_in_c_string_is_highlighted() {
+        let code = r#"char *cmd = "echo $(date)";"#;
+        let tokens = highlight_with_injection(code, Language::C);
+        let texts = token_texts(code, &tokens);
+
+        assert!(texts
+            .iter()
+            .any(|(text, ty)| *text == "date" && *ty == TokenType::Identifier));
+    }
+
+    #[test]
+    fn test_fenced_code_block_in_comment_is_highlighted() {
+        let code = "// ```bash\n// echo hi\n// ```\n";
+        let tokens = highlight_with_injection(code, Language::C);
+
+        assert!(tokens
+            .iter()
+            .any(|t| t.token_type == TokenType::Keyword && &code[t.start..t.end] == "echo"));
+    }
+
+    #[test]
+    fn test_plain_string_is_not_split() {
+        let code = r#"char *s = "just text";"#;
+        let tokens = highlight_with_injection(code, Language::C);
+
+        assert!(tokens
+            .iter()
+            .any(|t| t.token_type == TokenType::String && &code[t.start..t.end] == "\"just text\""));
+    }
+
+    #[test]
+    fn test_empty_command_substitution_is_skipped() {
+        let code = r#"char *s = "echo $()";"#;
+        let tokens = highlight_with_injection(code, Language::C);
+
+        assert!(tokens
+            .iter()
+            .any(|t| t.token_type == TokenType::String && &code[t.start..t.end] == "\"echo $()\""));
+    }
+}
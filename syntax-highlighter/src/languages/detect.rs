@@ -0,0 +1,292 @@
+/// Filename/shebang/glob-based language auto-detection.
+///
+/// This is deliberately independent from the content-heuristic
+/// `detect_language` in the parent module: it only looks at the filename and
+/// (optionally) the first line, so it's cheap to call on every file a site
+/// renders, and callers can register their own mappings at runtime instead
+/// of patching this file for site-specific conventions.
+use std::sync::{Mutex, OnceLock};
+
+use crate::tokenizer::Language;
+
+#[derive(Debug, Clone)]
+struct CustomMapping {
+    pattern: String,
+    language: Language,
+}
+
+fn registry() -> &'static Mutex<Vec<CustomMapping>> {
+    static REGISTRY: OnceLock<Mutex<Vec<CustomMapping>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+/// Register a custom `glob -> Language` mapping, consulted before any
+/// built-in rule. Patterns support a single leading `*` wildcard (e.g.
+/// `*.inc`) or an exact (case-insensitive) filename match (e.g. `Kconfig`).
+/// Later registrations take priority over earlier ones.
+pub fn register_mapping(glob_pattern: &str, language: Language) {
+    registry().lock().unwrap().push(CustomMapping {
+        pattern: glob_pattern.to_lowercase(),
+        language,
+    });
+}
+
+/// Clear all custom mappings. Mostly useful for tests.
+pub fn clear_mappings() {
+    registry().lock().unwrap().clear();
+}
+
+fn matches_glob(pattern: &str, filename: &str) -> bool {
+    match pattern.strip_prefix('*') {
+        Some(suffix) => filename.ends_with(suffix),
+        None => filename == pattern,
+    }
+}
+
+fn custom_match(filename: &str) -> Option<Language> {
+    let mappings = registry().lock().unwrap();
+    mappings
+        .iter()
+        .rev()
+        .find(|m| matches_glob(&m.pattern, filename))
+        .map(|m| m.language)
+}
+
+fn detect_by_exact_filename(name: &str) -> Option<Language> {
+    match name {
+        "makefile" | "gnumakefile" => Some(Language::Makefile),
+        "dockerfile" => Some(Language::Bash),
+        "bashrc" | ".bashrc" => Some(Language::Bash),
+        ".clang-format" | ".clang-tidy" => Some(Language::Yaml),
+        _ => None,
+    }
+}
+
+fn detect_by_extension(name: &str) -> Option<Language> {
+    const C_EXTENSIONS: &[&str] = &[".c", ".h", ".cpp", ".hpp", ".cc", ".cxx"];
+    const BASH_EXTENSIONS: &[&str] = &[".sh", ".bash", ".zsh"];
+    const YAML_EXTENSIONS: &[&str] = &[".yml", ".yaml"];
+    const MAKEFILE_EXTENSIONS: &[&str] = &[".mk"];
+
+    if C_EXTENSIONS.iter().any(|ext| name.ends_with(ext)) {
+        return Some(Language::C);
+    }
+    if BASH_EXTENSIONS.iter().any(|ext| name.ends_with(ext)) {
+        return Some(Language::Bash);
+    }
+    if YAML_EXTENSIONS.iter().any(|ext| name.ends_with(ext)) {
+        return Some(Language::Yaml);
+    }
+    if MAKEFILE_EXTENSIONS.iter().any(|ext| name.ends_with(ext)) {
+        return Some(Language::Makefile);
+    }
+
+    None
+}
+
+/// `interpreter name -> Language` table for shebang lines, covering both
+/// `#!/path/to/<interp>` and `#!/usr/bin/env <interp>` forms. Adding support
+/// for a new interpreter is a table edit here, not a new branch below.
+const INTERPRETER_LANGUAGES: &[(&str, Language)] = &[
+    ("bash", Language::Bash),
+    ("sh", Language::Bash),
+    ("zsh", Language::Bash),
+    ("ksh", Language::Bash),
+    ("dash", Language::Bash),
+    ("python", Language::Generic),
+    ("python3", Language::Generic),
+    ("node", Language::Generic),
+    ("nodejs", Language::Generic),
+    ("ruby", Language::Generic),
+    ("perl", Language::Generic),
+];
+
+fn interpreter_language(interpreter: &str) -> Option<Language> {
+    INTERPRETER_LANGUAGES
+        .iter()
+        .find(|(name, _)| *name == interpreter)
+        .map(|(_, lang)| *lang)
+        .or_else(|| {
+            // Versioned interpreters we don't enumerate, e.g. `python3.11`.
+            interpreter
+                .starts_with("python")
+                .then_some(Language::Generic)
+        })
+}
+
+/// `interpreter name -> line-comment prefix` for `Language::Generic`
+/// sources: there's no dedicated grammar to tell `#!/usr/bin/env node`'s
+/// `//` comments from Python/Ruby/Perl's `#` ones, so this is consulted
+/// separately from `INTERPRETER_LANGUAGES` to configure
+/// `Tokenizer::set_line_comment_prefix`. Interpreters absent here (and the
+/// default, unrecognized case) use `#`, `Tokenizer`'s own default.
+const INTERPRETER_COMMENT_PREFIXES: &[(&str, &str)] = &[("node", "//"), ("nodejs", "//")];
+
+pub fn interpreter_comment_prefix(interpreter: &str) -> Option<&'static str> {
+    INTERPRETER_COMMENT_PREFIXES
+        .iter()
+        .find(|(name, _)| *name == interpreter)
+        .map(|(_, prefix)| *prefix)
+}
+
+/// Line-comment prefix to configure a `Language::Generic` tokenizer with,
+/// sniffed from `first_line`'s shebang (falling back to `#` when there's no
+/// shebang, or its interpreter isn't in `INTERPRETER_COMMENT_PREFIXES`).
+pub fn generic_comment_prefix(first_line: Option<&str>) -> &'static str {
+    first_line
+        .and_then(shebang_interpreter)
+        .and_then(interpreter_comment_prefix)
+        .unwrap_or("#")
+}
+
+/// Extract the interpreter name from a shebang line, unwrapping
+/// `/usr/bin/env <interp> [args...]` and stripping any leading path from a
+/// direct `#!/path/to/<interp>` invocation.
+fn shebang_interpreter(line: &str) -> Option<&str> {
+    let rest = line.trim_start().strip_prefix("#!")?.trim();
+    let first_word = rest.split_whitespace().next()?;
+
+    if first_word.ends_with("/env") {
+        rest.split_whitespace().nth(1)
+    } else {
+        first_word.rsplit('/').next()
+    }
+}
+
+fn detect_by_shebang(first_line: &str) -> Option<Language> {
+    let line = first_line.trim_start();
+
+    if line.starts_with("#!") {
+        if let Some(lang) = shebang_interpreter(line).and_then(interpreter_language) {
+            return Some(lang);
+        }
+    }
+
+    if line.starts_with("%YAML") || line.starts_with("---") {
+        return Some(Language::Yaml);
+    }
+
+    None
+}
+
+/// Resolve a concrete `Language` from `filename` and/or `first_line`,
+/// consulting custom mappings first, then exact filename matches, then
+/// extension globs, then shebang/first-line sniffing. Falls back to
+/// `Language::Auto` when nothing matches.
+pub fn detect_language(filename: Option<&str>, first_line: Option<&str>) -> Language {
+    if let Some(name) = filename {
+        let lower = name.to_lowercase();
+
+        if let Some(lang) = custom_match(&lower) {
+            return lang;
+        }
+        if let Some(lang) = detect_by_exact_filename(&lower) {
+            return lang;
+        }
+        if let Some(lang) = detect_by_extension(&lower) {
+            return lang;
+        }
+    }
+
+    if let Some(line) = first_line {
+        if let Some(lang) = detect_by_shebang(line) {
+            return lang;
+        }
+    }
+
+    Language::Auto
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_filename_matches() {
+        assert_eq!(detect_language(Some("Makefile"), None), Language::Makefile);
+        assert_eq!(detect_language(Some("Dockerfile"), None), Language::Bash);
+        assert_eq!(detect_language(Some(".clang-format"), None), Language::Yaml);
+        assert_eq!(detect_language(Some("bashrc"), None), Language::Bash);
+        assert_eq!(detect_language(Some(".bashrc"), None), Language::Bash);
+    }
+
+    #[test]
+    fn test_extension_globs() {
+        assert_eq!(detect_language(Some("main.c"), None), Language::C);
+        assert_eq!(detect_language(Some("install.sh"), None), Language::Bash);
+        assert_eq!(detect_language(Some("rules.mk"), None), Language::Makefile);
+        assert_eq!(detect_language(Some("config.yaml"), None), Language::Yaml);
+    }
+
+    #[test]
+    fn test_shebang_and_yaml_markers() {
+        assert_eq!(
+            detect_language(None, Some("#!/usr/bin/env bash")),
+            Language::Bash
+        );
+        assert_eq!(detect_language(None, Some("%YAML 1.2")), Language::Yaml);
+        assert_eq!(detect_language(None, Some("---")), Language::Yaml);
+    }
+
+    #[test]
+    fn test_shebang_path_based_interpreters() {
+        assert_eq!(detect_language(None, Some("#!/bin/zsh")), Language::Bash);
+        assert_eq!(detect_language(None, Some("#!/bin/dash")), Language::Bash);
+        assert_eq!(
+            detect_language(None, Some("#!/usr/bin/python3")),
+            Language::Generic
+        );
+    }
+
+    #[test]
+    fn test_shebang_env_wrapped_interpreters() {
+        assert_eq!(
+            detect_language(None, Some("#!/usr/bin/env python")),
+            Language::Generic
+        );
+        assert_eq!(
+            detect_language(None, Some("#!/usr/bin/env node")),
+            Language::Generic
+        );
+        assert_eq!(
+            detect_language(None, Some("#!/usr/bin/env zsh")),
+            Language::Bash
+        );
+    }
+
+    #[test]
+    fn test_shebang_versioned_python_falls_back_to_generic() {
+        assert_eq!(
+            detect_language(None, Some("#!/usr/bin/env python3.11")),
+            Language::Generic
+        );
+    }
+
+    #[test]
+    fn test_falls_back_to_auto() {
+        assert_eq!(detect_language(None, None), Language::Auto);
+        assert_eq!(detect_language(Some("notes.txt"), None), Language::Auto);
+    }
+
+    #[test]
+    fn test_custom_mapping_takes_priority() {
+        register_mapping("*.inc", Language::Makefile);
+        assert_eq!(detect_language(Some("rules.inc"), None), Language::Makefile);
+        // `.clang-tidy` is a built-in exact-filename rule (Yaml).
+        register_mapping(".clang-tidy", Language::Makefile);
+        assert_eq!(detect_language(Some(".clang-tidy"), None), Language::Makefile);
+        clear_mappings();
+    }
+
+    #[test]
+    fn test_generic_comment_prefix_is_node_specific() {
+        assert_eq!(
+            generic_comment_prefix(Some("#!/usr/bin/env node")),
+            "//"
+        );
+        assert_eq!(generic_comment_prefix(Some("#!/usr/bin/env nodejs")), "//");
+        assert_eq!(generic_comment_prefix(Some("#!/usr/bin/env python")), "#");
+        assert_eq!(generic_comment_prefix(Some("#!/usr/bin/env ruby")), "#");
+        assert_eq!(generic_comment_prefix(None), "#");
+    }
+}
@@ -1,87 +1,216 @@
 pub mod c;
 pub mod bash;
+pub mod detect;
 pub mod makefile;
+pub mod syntax_mapping;
+pub mod treesitter;
 pub mod yaml;
 
+use std::collections::HashMap;
+
 use crate::tokenizer::{Language, TokenType};
 
-/// Language detection based on file extension or content analysis
+/// Below this score, `detect_language_scored` reports `Language::Auto`
+/// instead of the (too weakly supported) top scorer.
+const DETECTION_THRESHOLD: f64 = 0.5;
+
+/// Weighted, Linguist-style language detection: every signal casts a vote
+/// into a score map instead of returning on the first hard-coded match, so
+/// adding a new signal is an additive change rather than an ordering
+/// decision. Strong signals (extension, shebang) are worth +10, medium
+/// signals (`Makefile*`-style filename patterns) +5, and weak content
+/// heuristics +0.5 to +2 each (and can fire more than once).
 pub fn detect_language(content: &str, filename: Option<&str>) -> Language {
-    // Try filename first
+    syntax_mapping::detect_language_with_mapping(
+        content,
+        filename,
+        &syntax_mapping::SyntaxMapping::new(),
+    )
+}
+
+/// Like `detect_language`, but also returns the winning score so callers can
+/// judge how confident the detection was.
+pub fn detect_language_scored(content: &str, filename: Option<&str>) -> (Language, f64) {
+    let mut scores: HashMap<Language, f64> = HashMap::new();
+    let mut bump = |lang: Language, amount: f64| {
+        *scores.entry(lang).or_insert(0.0) += amount;
+    };
+
     if let Some(name) = filename {
         let name = name.to_lowercase();
-        if name.ends_with(".c") || name.ends_with(".h") || name.ends_with(".cpp") || name.ends_with(".hpp") {
-            return Language::C;
+
+        // Medium: filename pattern rather than a strict extension or exact
+        // match (those are covered by `detect::detect_language` below).
+        if name == "makefile" || name.starts_with("makefile") {
+            bump(Language::Makefile, 5.0);
         }
-        if name.ends_with(".sh") || name.ends_with(".bash") || name == "bashrc" || name == ".bashrc" {
-            return Language::Bash;
+    }
+
+    // Strong: exact/extension filename match, or a recognized shebang /
+    // document-start marker on the first line. Delegates to `detect::
+    // detect_language` (rather than maintaining a second extension/
+    // interpreter table here) so the two detectors can't drift apart.
+    let first_line = content.trim_start().lines().next();
+    let strong_match = detect::detect_language(filename, first_line);
+    if strong_match != Language::Auto {
+        bump(strong_match, 10.0);
+    }
+
+    // High-priority, just below an explicit filename extension: an editor
+    // modeline is an explicit author declaration, common on extensionless
+    // kernel source and dotfiles.
+    if let Some(lang) = detect_modeline_language(content) {
+        bump(lang, 9.0);
+    }
+
+    // Weak, repeatable content heuristics.
+    bump(Language::C, content.matches("#include").count() as f64 * 2.0);
+    if content.contains("int main") {
+        bump(Language::C, 2.0);
+    }
+    if content.contains("printf") {
+        bump(Language::C, 1.0);
+    }
+    if content.contains("MODULE_") {
+        bump(Language::C, 1.0);
+    }
+
+    bump(Language::Makefile, content.matches("$(").count() as f64);
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') {
+            continue;
+        }
+
+        if line.starts_with('\t') && !line.trim().is_empty() {
+            bump(Language::Makefile, 1.0);
+            // Disambiguation: a tab-indented line whose colon has no space
+            // after it reads as a Makefile rule, not a YAML value.
+            if let Some((before, after)) = trimmed.split_once(':') {
+                if !before.is_empty() && !after.starts_with(' ') && !after.is_empty() {
+                    bump(Language::Makefile, 1.5);
+                }
+            }
         }
-        if name == "makefile" || name.ends_with(".mk") || name.starts_with("makefile") {
-            return Language::Makefile;
+
+        if trimmed.contains(": ") {
+            bump(Language::Yaml, 0.5);
         }
-        if name.ends_with(".yml") || name.ends_with(".yaml") {
-            return Language::Yaml;
+        if trimmed.starts_with("- ") {
+            bump(Language::Yaml, 0.5);
         }
     }
 
-    // Content-based detection for code fence languages
-    if content.trim_start().starts_with("#!/bin/bash") || 
-       content.trim_start().starts_with("#!/bin/sh") {
-        return Language::Bash;
+    // Anchored to line-start, like `detect::detect_by_shebang`'s
+    // `"---"`/`"%YAML"` check, rather than an anywhere-substring match --
+    // otherwise a C comment or decrement (`a---b`) wins a YAML vote it has
+    // no business casting.
+    if content.lines().any(|line| line.trim_start().starts_with("---")) {
+        bump(Language::Yaml, 2.0);
     }
 
-    // Look for C-style patterns
-    if content.contains("#include") || 
-       content.contains("int main") || 
-       content.contains("printf") ||
-       content.contains("MODULE_") {
-        return Language::C;
+    // `.h` is ambiguous between C and C++; without a dedicated Cpp variant
+    // in `Language` this is a no-op today, but is where a C++-only-token
+    // check (e.g. `class`, `template`, `namespace`) would tip the balance.
+
+    match scores
+        .iter()
+        .max_by(|a, b| {
+            a.1.partial_cmp(b.1)
+                .unwrap()
+                .then_with(|| tie_break_rank(*b.0).cmp(&tie_break_rank(*a.0)))
+        })
+        .map(|(&lang, &score)| (lang, score))
+    {
+        Some((lang, score)) if score >= DETECTION_THRESHOLD => (lang, score),
+        _ => (Language::Auto, 0.0),
     }
+}
 
-    // Look for Makefile patterns (strong indicators first)
-    if content.contains("$(") || 
-       content.contains("\t") { // Makefiles use tabs for commands (very strong indicator)
-        return Language::Makefile;
+/// Fixed priority order used to break exact score ties deterministically
+/// (lower rank wins), since iterating a `HashMap` in score order alone
+/// would otherwise pick a different winner on every run.
+fn tie_break_rank(lang: Language) -> u8 {
+    match lang {
+        Language::C => 0,
+        Language::Bash => 1,
+        Language::Makefile => 2,
+        Language::Yaml => 3,
+        Language::Generic => 4,
+        Language::Auto => 5,
     }
+}
 
-    // Check for Makefile target patterns
-    let has_makefile_target = content.lines().any(|line| {
-        let trimmed = line.trim();
-        if trimmed.contains(":") && !trimmed.starts_with("#") {
-            // Split on colon
-            if let Some(before_colon) = trimmed.split(':').next() {
-                let before_colon = before_colon.trim();
-                // Makefile targets are typically single identifiers
-                if !before_colon.is_empty() && 
-                   !before_colon.contains(' ') &&
-                   before_colon.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.') {
-                    // This looks like a makefile target
-                    return true;
-                }
+/// Map an editor modeline's mode/filetype name to our `Language` enum.
+fn modeline_name_to_language(name: &str) -> Option<Language> {
+    match name.to_lowercase().as_str() {
+        "c" | "cpp" | "c++" => Some(Language::C),
+        "sh" | "bash" | "zsh" => Some(Language::Bash),
+        "make" | "makefile" => Some(Language::Makefile),
+        "yaml" | "yml" => Some(Language::Yaml),
+        _ => None,
+    }
+}
+
+/// Emacs local-variables modeline, e.g. `-*- mode: c -*-` or
+/// `-*- mode: makefile; coding: utf-8 -*-`, and the bare `-*- c -*-` form.
+fn parse_emacs_modeline(line: &str) -> Option<Language> {
+    let start = line.find("-*-")?;
+    let rest = &line[start + 3..];
+    let end = rest.find("-*-")?;
+    let body = &rest[..end];
+
+    for part in body.split(';') {
+        let part = part.trim();
+        if let Some(mode) = part.strip_prefix("mode:") {
+            if let Some(lang) = modeline_name_to_language(mode.trim()) {
+                return Some(lang);
+            }
+        } else if !part.contains(':') {
+            if let Some(lang) = modeline_name_to_language(part) {
+                return Some(lang);
             }
         }
-        false
-    });
+    }
 
-    // Look for YAML patterns
-    let has_yaml_pattern = content.contains("---") || 
-       content.lines().any(|line| {
-           let trimmed = line.trim();
-           // YAML key-value pairs: key: value with space after colon
-           (trimmed.contains(": ") && !trimmed.starts_with("#")) ||
-           // YAML list items starting with dash
-           (trimmed.starts_with("- ") && !trimmed.starts_with("#"))
-       });
+    None
+}
 
-    if has_makefile_target && !has_yaml_pattern {
-        return Language::Makefile;
+/// Vim modeline, e.g. `vim: set ft=yaml:`, `vim: filetype=sh`, or
+/// `# vi: ft=make`.
+fn parse_vim_modeline(line: &str) -> Option<Language> {
+    let trimmed = line.trim();
+    let marker = trimmed.rfind("vim:").or_else(|| trimmed.rfind("vi:"))?;
+    let after_marker = marker + trimmed[marker..].find(':')? + 1;
+    let rest = &trimmed[after_marker..];
+
+    for part in rest.split([' ', ':']) {
+        let part = part.trim_end_matches(':');
+        if let Some(ft) = part.strip_prefix("ft=").or_else(|| part.strip_prefix("filetype=")) {
+            if let Some(lang) = modeline_name_to_language(ft) {
+                return Some(lang);
+            }
+        }
     }
 
-    if has_yaml_pattern {
-        return Language::Yaml;
+    None
+}
+
+/// Scan the first and last few lines of `content` for an Emacs or Vim
+/// modeline declaring the file's mode/filetype.
+fn detect_modeline_language(content: &str) -> Option<Language> {
+    let lines: Vec<&str> = content.lines().collect();
+    let head = lines.iter().take(5);
+    let tail = lines.iter().rev().take(5);
+
+    for line in head.chain(tail) {
+        if let Some(lang) = parse_emacs_modeline(line).or_else(|| parse_vim_modeline(line)) {
+            return Some(lang);
+        }
     }
 
-    Language::Auto
+    None
 }
 
 /// Get CSS class name for token type
@@ -91,10 +220,16 @@ pub fn get_css_class(token_type: &TokenType) -> &'static str {
         TokenType::Identifier => "hl-identifier",
         TokenType::Function => "hl-function",
         TokenType::String => "hl-string",
+        TokenType::StringInterpolation => "hl-string-interpolation",
         TokenType::Number => "hl-number",
         TokenType::Comment => "hl-comment",
         TokenType::Operator => "hl-operator",
         TokenType::Punctuation => "hl-punctuation",
+        TokenType::Key => "hl-key",
+        TokenType::Value => "hl-value",
+        TokenType::Anchor => "hl-anchor",
+        TokenType::Alias => "hl-alias",
+        TokenType::Tag => "hl-tag",
         TokenType::Whitespace => "hl-whitespace",
         TokenType::Unknown => "hl-unknown",
     }
@@ -315,4 +450,88 @@ mod tests {
         assert_eq!(detect_language("$()", None), Language::Makefile); // Has $( pattern
         assert_eq!(detect_language("---", None), Language::Yaml); // Has YAML marker
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_detect_language_scored_confidence() {
+        let (lang, score) = detect_language_scored("", Some("main.c"));
+        assert_eq!(lang, Language::C);
+        assert!(score >= 10.0);
+
+        let (lang, score) = detect_language_scored("just some prose", None);
+        assert_eq!(lang, Language::Auto);
+        assert_eq!(score, 0.0);
+    }
+
+    #[test]
+    fn test_repeated_weak_signals_accumulate() {
+        // A single "$(" is enough to lean Makefile, but more occurrences
+        // should only make that verdict more confident, not less.
+        let (_, low) = detect_language_scored("$(CC)", None);
+        let (_, high) = detect_language_scored("$(CC) $(CFLAGS) $(LD)", None);
+        assert!(high > low);
+    }
+
+    #[test]
+    fn test_emacs_modeline_detection() {
+        assert_eq!(
+            detect_language("/* -*- mode: c -*- */\nint x;", None),
+            Language::C
+        );
+        assert_eq!(
+            detect_language("# -*- mode: makefile -*-\nall:\n", None),
+            Language::Makefile
+        );
+    }
+
+    #[test]
+    fn test_vim_modeline_detection() {
+        assert_eq!(
+            detect_language("# vim: set ft=yaml:\nkey: value", None),
+            Language::Yaml
+        );
+        assert_eq!(
+            detect_language("# vi: filetype=sh", None),
+            Language::Bash
+        );
+    }
+
+    #[test]
+    fn test_tie_break_is_deterministic_by_language_priority() {
+        // "$(CC)" bumps Makefile by 1.0; the two ": "-containing lines bump
+        // Yaml by 0.5 each -- an exact 1.0-vs-1.0 tie that, without a fixed
+        // tie-break, would resolve to whichever language `HashMap`
+        // iteration happened to visit last (different on every run).
+        let content = "key: value\nother: thing\n$(CC)";
+        let (lang, score) = detect_language_scored(content, None);
+        assert_eq!(lang, Language::Makefile);
+        assert_eq!(score, 1.0);
+    }
+
+    #[test]
+    fn test_scored_detection_routes_through_shared_detect_module() {
+        // These all rely on tables that only ever lived in `detect::
+        // detect_language` (exact filenames, the interpreter table); the
+        // scored classifier must delegate to it rather than keep its own
+        // narrower, diverging copy.
+        assert_eq!(detect_language("", Some("Dockerfile")), Language::Bash);
+        assert_eq!(detect_language("", Some(".clang-format")), Language::Yaml);
+        assert_eq!(
+            detect_language("#!/usr/bin/env python\nprint('hi')", None),
+            Language::Generic
+        );
+        assert_eq!(
+            detect_language("#!/usr/bin/env zsh\necho hi", None),
+            Language::Bash
+        );
+    }
+
+    #[test]
+    fn test_mid_line_triple_dash_does_not_outweigh_c_signal() {
+        // A block-comment separator (or a plain decrement like `a---b`)
+        // isn't a YAML document marker -- only a line-start `---` is.
+        assert_eq!(
+            detect_language("#include <x>\n/* --- */\n", None),
+            Language::C
+        );
+    }
+}
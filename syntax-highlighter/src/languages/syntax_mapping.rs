@@ -0,0 +1,133 @@
+/// User-supplied syntax mapping overrides, modeled on bat's `--map-syntax`.
+///
+/// A `SyntaxMapping` is an ordered list of glob and content-substring rules
+/// that are consulted *before* the built-in `detect_language` heuristics,
+/// so a site can force e.g. every `*.inc` file (or anything containing a
+/// particular marker) to a specific `Language` without touching
+/// `detect_language` itself.
+use crate::tokenizer::Language;
+
+use super::detect_language_scored;
+
+#[derive(Debug, Clone, Default)]
+pub struct SyntaxMapping {
+    /// Filename glob -> Language, in registration order.
+    glob_rules: Vec<(String, Language)>,
+    /// Content substring -> Language, in registration order (a lightweight
+    /// stand-in for a content regex, since matching is just "does this
+    /// marker appear").
+    content_rules: Vec<(String, Language)>,
+}
+
+impl SyntaxMapping {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Map a filename glob (a literal name, or `*suffix` such as `*.inc`)
+    /// to a `Language`. Later registrations win over earlier ones.
+    pub fn map_glob(&mut self, pattern: &str, language: Language) -> &mut Self {
+        self.glob_rules.push((pattern.to_string(), language));
+        self
+    }
+
+    /// Map a content marker (e.g. a fenced-block info-string keyword) to a
+    /// `Language`. Later registrations win over earlier ones.
+    pub fn map_content(&mut self, marker: &str, language: Language) -> &mut Self {
+        self.content_rules.push((marker.to_string(), language));
+        self
+    }
+
+    fn matches_glob(pattern: &str, filename: &str) -> bool {
+        match pattern.strip_prefix('*') {
+            Some(suffix) => filename.ends_with(suffix),
+            None => filename == pattern,
+        }
+    }
+
+    /// Resolve `content`/`filename` against this mapping's rules, without
+    /// falling back to the built-in heuristics.
+    pub fn resolve(&self, content: &str, filename: Option<&str>) -> Option<Language> {
+        if let Some(name) = filename {
+            let lower = name.to_lowercase();
+            if let Some(lang) = self
+                .glob_rules
+                .iter()
+                .rev()
+                .find(|(pattern, _)| Self::matches_glob(&pattern.to_lowercase(), &lower))
+                .map(|(_, lang)| *lang)
+            {
+                return Some(lang);
+            }
+        }
+
+        self.content_rules
+            .iter()
+            .rev()
+            .find(|(marker, _)| content.contains(marker.as_str()))
+            .map(|(_, lang)| *lang)
+    }
+}
+
+/// Like `detect_language`, but consults `mapping` first. `detect_language`
+/// is a thin wrapper around this with an empty mapping, so existing callers
+/// and tests are unaffected.
+pub fn detect_language_with_mapping(
+    content: &str,
+    filename: Option<&str>,
+    mapping: &SyntaxMapping,
+) -> Language {
+    if let Some(lang) = mapping.resolve(content, filename) {
+        return lang;
+    }
+
+    detect_language_scored(content, filename).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_override_takes_priority() {
+        let mut mapping = SyntaxMapping::new();
+        mapping.map_glob("*.inc", Language::Makefile);
+
+        assert_eq!(
+            detect_language_with_mapping("key: value", Some("rules.inc"), &mapping),
+            Language::Makefile
+        );
+    }
+
+    #[test]
+    fn test_content_override() {
+        let mut mapping = SyntaxMapping::new();
+        mapping.map_content("language-yaml", Language::Yaml);
+
+        assert_eq!(
+            detect_language_with_mapping("```language-yaml\nfoo: bar\n```", None, &mapping),
+            Language::Yaml
+        );
+    }
+
+    #[test]
+    fn test_falls_back_to_builtin_heuristics() {
+        let mapping = SyntaxMapping::new();
+        assert_eq!(
+            detect_language_with_mapping("#include <stdio.h>", None, &mapping),
+            Language::C
+        );
+    }
+
+    #[test]
+    fn test_later_registration_wins() {
+        let mut mapping = SyntaxMapping::new();
+        mapping.map_glob("Kconfig", Language::Yaml);
+        mapping.map_glob("Kconfig", Language::Makefile);
+
+        assert_eq!(
+            detect_language_with_mapping("", Some("Kconfig"), &mapping),
+            Language::Makefile
+        );
+    }
+}
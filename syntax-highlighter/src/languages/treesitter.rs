@@ -0,0 +1,174 @@
+/// Optional tree-sitter backed highlighting.
+///
+/// This backend parses source into a real concrete syntax tree and maps
+/// tree-sitter highlight captures onto our `TokenType` enum, so it emits the
+/// exact same `Vec<Token>` shape as the hand-rolled `Tokenizer`. It is only
+/// compiled in when the `treesitter` feature is enabled; callers that don't
+/// opt in keep using the fallback tokenizer with zero extra dependencies.
+#[cfg(feature = "treesitter")]
+use std::collections::HashMap;
+#[cfg(feature = "treesitter")]
+use std::sync::OnceLock;
+
+#[cfg(feature = "treesitter")]
+use tree_sitter_highlight::{HighlightConfiguration, HighlightEvent, Highlighter};
+
+use crate::tokenizer::{Language, Token, TokenType};
+
+/// Capture names we ask each `highlights.scm` query to produce. Order does
+/// not matter here; tree_sitter_highlight resolves a capture name to its
+/// index in this list and reports that index back in `HighlightEvent`.
+#[cfg(feature = "treesitter")]
+const CAPTURE_NAMES: &[&str] = &[
+    "keyword",
+    "function",
+    "string",
+    "comment",
+    "number",
+    "operator",
+    "punctuation",
+    "variable",
+    "type",
+];
+
+#[cfg(feature = "treesitter")]
+fn capture_to_token_type(name: &str) -> TokenType {
+    match name {
+        "keyword" => TokenType::Keyword,
+        "function" => TokenType::Function,
+        "string" => TokenType::String,
+        "comment" => TokenType::Comment,
+        "number" => TokenType::Number,
+        "operator" => TokenType::Operator,
+        "punctuation" => TokenType::Punctuation,
+        "type" => TokenType::Keyword,
+        "variable" => TokenType::Identifier,
+        _ => TokenType::Unknown,
+    }
+}
+
+/// Lazily-built grammar + query registry, one `HighlightConfiguration` per
+/// supported `Language`. Building a `HighlightConfiguration` compiles the
+/// query against the grammar, so we only want to pay that cost once.
+#[cfg(feature = "treesitter")]
+struct GrammarRegistry {
+    configs: HashMap<Language, HighlightConfiguration>,
+}
+
+#[cfg(feature = "treesitter")]
+impl GrammarRegistry {
+    fn new() -> Self {
+        let mut configs = HashMap::new();
+
+        if let Some(config) = build_config(
+            tree_sitter_c::LANGUAGE.into(),
+            "c",
+            tree_sitter_c::HIGHLIGHTS_QUERY,
+        ) {
+            configs.insert(Language::C, config);
+        }
+
+        if let Some(config) = build_config(
+            tree_sitter_bash::LANGUAGE.into(),
+            "bash",
+            tree_sitter_bash::HIGHLIGHT_QUERY,
+        ) {
+            configs.insert(Language::Bash, config);
+        }
+
+        if let Some(config) = build_config(
+            tree_sitter_make::LANGUAGE.into(),
+            "make",
+            tree_sitter_make::HIGHLIGHTS_QUERY,
+        ) {
+            configs.insert(Language::Makefile, config);
+        }
+
+        if let Some(config) = build_config(
+            tree_sitter_yaml::LANGUAGE.into(),
+            "yaml",
+            tree_sitter_yaml::HIGHLIGHTS_QUERY,
+        ) {
+            configs.insert(Language::Yaml, config);
+        }
+
+        Self { configs }
+    }
+
+    fn get(&self, language: Language) -> Option<&HighlightConfiguration> {
+        self.configs.get(&language)
+    }
+}
+
+#[cfg(feature = "treesitter")]
+fn build_config(
+    grammar: tree_sitter::Language,
+    name: &'static str,
+    highlights_query: &'static str,
+) -> Option<HighlightConfiguration> {
+    let mut config =
+        HighlightConfiguration::new(grammar, name, highlights_query, "", "").ok()?;
+    config.configure(CAPTURE_NAMES);
+    Some(config)
+}
+
+#[cfg(feature = "treesitter")]
+static REGISTRY: OnceLock<GrammarRegistry> = OnceLock::new();
+
+#[cfg(feature = "treesitter")]
+fn registry() -> &'static GrammarRegistry {
+    REGISTRY.get_or_init(GrammarRegistry::new)
+}
+
+/// Highlight `code` using the tree-sitter grammar registered for
+/// `language`, flattening overlapping captures to the innermost match per
+/// byte range. Returns `None` when no grammar is registered for `language`
+/// (including `Language::Auto`) so the caller can fall back to the plain
+/// `Tokenizer`.
+#[cfg(feature = "treesitter")]
+pub fn highlight_with_treesitter(code: &str, language: Language) -> Option<Vec<Token>> {
+    let config = registry().get(language)?;
+
+    let mut highlighter = Highlighter::new();
+    let events = highlighter
+        .highlight(config, code.as_bytes(), None, |_| None)
+        .ok()?;
+
+    let mut tokens = Vec::new();
+    let mut active: Vec<&'static str> = Vec::new();
+
+    for event in events {
+        match event.ok()? {
+            HighlightEvent::HighlightStart(highlight) => {
+                active.push(CAPTURE_NAMES[highlight.0]);
+            }
+            HighlightEvent::HighlightEnd => {
+                active.pop();
+            }
+            HighlightEvent::Source { start, end } => {
+                if start == end {
+                    continue;
+                }
+                let token_type = active
+                    .last()
+                    .map(|name| capture_to_token_type(name))
+                    .unwrap_or(TokenType::Unknown);
+
+                tokens.push(Token {
+                    token_type,
+                    start,
+                    end,
+                });
+            }
+        }
+    }
+
+    Some(tokens)
+}
+
+/// Without the `treesitter` feature enabled there is no grammar registry to
+/// consult; every caller falls back to the hand-rolled `Tokenizer`.
+#[cfg(not(feature = "treesitter"))]
+pub fn highlight_with_treesitter(_code: &str, _language: Language) -> Option<Vec<Token>> {
+    None
+}
@@ -2,50 +2,33 @@
 
 pub struct YamlLanguage;
 
+/// `.nan`/`.inf` and their signed/cased variants. These never reach
+/// `is_keyword` through `read_identifier` (the leading `.`/`-`/`+` is
+/// consumed by `read_operator` first) -- `Tokenizer::read_yaml_construct`
+/// matches this same list directly against the input in value position, so
+/// this is the one place the literal forms are spelled out.
+pub const SPECIAL_FLOATS: &[&str] = &[
+    "-.inf", "-.Inf", "-.INF", "+.inf", "+.Inf", "+.INF",
+    ".inf", ".Inf", ".INF", ".nan", ".NaN", ".NAN",
+];
+
 impl YamlLanguage {
-    /// Enhanced YAML keyword and value detection
+    /// Literal scalar detection only. Structural field names (`image`,
+    /// `apiVersion`, ...) used to be hardcoded here, which both missed real
+    /// files and wrongly highlighted any value that happened to match one of
+    /// them; that structure is now derived positionally by the tokenizer
+    /// (see `Tokenizer::read_yaml_construct` and the `Key`/`Value` token
+    /// types), so this only needs to recognize YAML's literal value forms.
     pub fn is_keyword(text: &str) -> bool {
         matches!(text,
             // YAML boolean values
             "true" | "True" | "TRUE" | "false" | "False" | "FALSE" |
             "yes" | "Yes" | "YES" | "no" | "No" | "NO" |
             "on" | "On" | "ON" | "off" | "Off" | "OFF" |
-            
+
             // YAML null values
-            "null" | "Null" | "NULL" | "~" |
-            
-            // YAML special values
-            ".nan" | ".NaN" | ".NAN" | ".inf" | ".Inf" | ".INF" |
-            "-.inf" | "-.Inf" | "-.INF" | "+.inf" | "+.Inf" | "+.INF" |
-            
-            // Common YAML document markers
-            "---" | "..." |
-            
-            // Common CI/CD and configuration keywords
-            "version" | "name" | "description" | "author" | "license" | "main" |
-            "scripts" | "dependencies" | "devDependencies" | "keywords" |
-            "repository" | "bugs" | "homepage" | "engines" | "private" |
-            
-            // GitHub Actions / GitLab CI keywords
-            "jobs" | "runs-on" | "steps" | "uses" | "with" | "run" |
-            "env" | "if" | "needs" | "strategy" | "matrix" | "include" | "exclude" |
-            "services" | "container" | "volumes" | "ports" | "options" |
-            "timeout-minutes" | "continue-on-error" | "outputs" | "secrets" |
-            "workflow_dispatch" | "push" | "pull_request" | "schedule" | "cron" |
-            
-            // Docker Compose keywords
-            "networks" | "configs" |
-            "image" | "build" | "command" | "entrypoint" | "working_dir" |
-            "user" | "expose" | "environment" |
-            "env_file" | "depends_on" | "links" | "restart" |
-            
-            // Kubernetes keywords
-            "apiVersion" | "kind" | "metadata" | "spec" | "status" |
-            "namespace" | "annotations" | "selector" |
-            "template" | "containers" | "volumeMounts" |
-            "resources" | "limits" | "requests" | "cpu" | "memory" |
-            "replicas" | "rollingUpdate" | "maxSurge" | "maxUnavailable"
-        )
+            "null" | "Null" | "NULL" | "~"
+        ) || SPECIAL_FLOATS.contains(&text)
     }
 
 }
@@ -64,4 +47,12 @@ mod tests {
         assert!(!YamlLanguage::is_keyword("my_value"));
     }
 
+    #[test]
+    fn test_yaml_field_names_are_not_hardcoded_keywords() {
+        // These used to be hardcoded as keywords regardless of position;
+        // now the tokenizer decides Key vs Value positionally instead.
+        assert!(!YamlLanguage::is_keyword("image"));
+        assert!(!YamlLanguage::is_keyword("apiVersion"));
+    }
+
 }
\ No newline at end of file
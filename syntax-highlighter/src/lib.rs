@@ -1,10 +1,17 @@
 use wasm_bindgen::prelude::*;
 
+mod injection;
 mod languages;
+mod lines;
+mod rainbow;
+mod render;
+mod stats;
+mod theme;
 mod tokenizer;
 
-use languages::{detect_language, get_css_class};
-use tokenizer::{Language, Token, TokenType, Tokenizer};
+use languages::{detect_language, treesitter};
+use render::escape_html;
+use tokenizer::{Language, Token};
 
 // Import console.log for debugging
 #[wasm_bindgen]
@@ -18,21 +25,141 @@ macro_rules! console_log {
     ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
 }
 
+/// Resolve `language` (an explicit alias, or `None` to auto-detect) and
+/// tokenize `code`, preferring the tree-sitter backend when a grammar is
+/// registered for the resolved language; it understands real grammar
+/// nuances (templates, raw strings, anchors) that the hand-rolled tokenizer
+/// approximates. Falls back to the plain tokenizer (with sub-language
+/// injection) when no grammar is available, which keeps `Language::Auto`
+/// working exactly as before.
+fn resolve_and_tokenize(code: &str, language: Option<String>) -> Vec<Token> {
+    let detected_language = match language.as_deref() {
+        Some("c") | Some("cpp") | Some("c++") => Language::C,
+        Some("bash") | Some("sh") | Some("shell") => Language::Bash,
+        Some("makefile") | Some("make") => Language::Makefile,
+        Some("yaml") | Some("yml") => Language::Yaml,
+        Some("python") | Some("node") | Some("ruby") | Some("perl") | Some("generic") => {
+            Language::Generic
+        }
+        _ => detect_language(code, language.as_deref()),
+    };
+
+    match treesitter::highlight_with_treesitter(code, detected_language) {
+        Some(tokens) => tokens,
+        None => {
+            // Only meaningful for `Language::Generic`, but cheap enough to
+            // compute unconditionally: the explicit alias (`"node"`) wins
+            // over sniffing `code`'s own shebang, since it reflects what
+            // the caller actually asked for.
+            let comment_prefix = language
+                .as_deref()
+                .and_then(languages::detect::interpreter_comment_prefix)
+                .unwrap_or_else(|| {
+                    languages::detect::generic_comment_prefix(code.trim_start().lines().next())
+                });
+            injection::highlight_with_injection_and_comment_prefix(
+                code,
+                detected_language,
+                comment_prefix,
+            )
+        }
+    }
+}
+
 /// Main entry point for syntax highlighting
 #[wasm_bindgen]
 pub fn highlight_code(code: &str, language: Option<String>) -> String {
+    let tokens = resolve_and_tokenize(code, language);
+    generate_html_with_classes(code, &tokens)
+}
+
+/// Like `highlight_code`, but renders a JSON array of `{start, end,
+/// token_type, css_class}` spans instead of HTML, for callers that want to
+/// build their own DOM or diff against a previous render.
+#[wasm_bindgen]
+pub fn highlight_code_tokens(code: &str, language: Option<String>) -> String {
+    let tokens = resolve_and_tokenize(code, language);
+    let mut renderer = render::JsonRenderer::new();
+    render::render_tokens(code, &tokens, &mut renderer);
+    renderer.into_json()
+}
+
+/// Like `highlight_code`, but resolves each token's style from a `Theme`
+/// and emits inline `style="..."` spans instead of `hl-*` CSS classes, so
+/// the output is fully standalone HTML. `theme_json` is a JSON object of
+/// `{"keyword": {"color": "#...", "bold": true}, ...}` entries (see
+/// `theme::Theme::from_json`); pass `None`, or malformed JSON, to fall back
+/// to the built-in dark theme.
+#[wasm_bindgen]
+pub fn highlight_code_themed(
+    code: &str,
+    language: Option<String>,
+    theme_json: Option<String>,
+) -> String {
+    let tokens = resolve_and_tokenize(code, language);
+    let resolved_theme = theme_json
+        .as_deref()
+        .and_then(theme::Theme::from_json)
+        .unwrap_or_else(theme::Theme::dark);
+
+    let mut renderer = theme::ThemedHtmlRenderer::new(&resolved_theme);
+    render::render_tokens(code, &tokens, &mut renderer);
+    renderer.into_output()
+}
+
+/// Like `highlight_code`, but takes an options bag instead of a fixed
+/// output mode. Currently just `rainbow`: when set, matching `(){}[]`
+/// pairs are classed `hl-bracket-{depth}` (cycling every
+/// `rainbow::DEFAULT_BRACKET_CYCLE` levels) instead of `hl-punctuation`,
+/// and a stray closing bracket gets `hl-bracket-unmatched`.
+#[wasm_bindgen]
+pub fn highlight_code_opts(code: &str, language: Option<String>, rainbow: bool) -> String {
+    let tokens = resolve_and_tokenize(code, language);
+    rainbow::render_html(code, &tokens, rainbow, rainbow::DEFAULT_BRACKET_CYCLE)
+}
+
+/// Classify each physical line of `code` as code, comment-only, or blank
+/// (tokei-style) and return `{code, comments, blanks, total}` as JSON, so
+/// the website can render "42 lines, 8 comments" badges without a separate
+/// parser. Resolves `language` like `highlight_code`, then runs the plain
+/// `Tokenizer` (via `stats::stats_for`): no tree-sitter backend, no
+/// sub-language injection, and `#` line comments for `Language::Generic`.
+/// Where `highlight_code` uses any of those, the counts can differ from
+/// what it displays (e.g. `//` comments under the `"node"` alias count as
+/// code).
+#[wasm_bindgen]
+pub fn code_stats(code: &str, language: Option<String>) -> String {
     let detected_language = match language.as_deref() {
         Some("c") | Some("cpp") | Some("c++") => Language::C,
         Some("bash") | Some("sh") | Some("shell") => Language::Bash,
         Some("makefile") | Some("make") => Language::Makefile,
         Some("yaml") | Some("yml") => Language::Yaml,
+        Some("python") | Some("node") | Some("ruby") | Some("perl") | Some("generic") => {
+            Language::Generic
+        }
         _ => detect_language(code, language.as_deref()),
     };
 
-    let mut tokenizer = Tokenizer::new(code, detected_language);
-    let tokens = tokenizer.tokenize();
+    let stats = stats::stats_for(code, detected_language);
+    serde_json::to_string(&stats).unwrap_or_else(|_| "{}".to_string())
+}
 
-    generate_html_with_classes(code, &tokens)
+/// Like `highlight_code`, but wraps each physical line in a
+/// `<span class="hl-line" id="L{n}" data-line="{n}">...</span>`, so the
+/// website can deep-link to (`#L42`) and spotlight specific lines. Lines
+/// named by `highlight_ranges` (a `"3,7-9,20"`-style spec; pass `None` to
+/// highlight nothing) get an extra `hl-line-emphasis` class. A token that
+/// itself spans multiple lines (a block comment, a multi-line string) is
+/// split at each line boundary so no `hl-line` span ever straddles a
+/// newline.
+#[wasm_bindgen]
+pub fn highlight_code_lines(
+    code: &str,
+    language: Option<String>,
+    highlight_ranges: Option<String>,
+) -> String {
+    let tokens = resolve_and_tokenize(code, language);
+    lines::render_lines(code, &tokens, highlight_ranges.as_deref())
 }
 
 /// Apply highlighting to existing DOM element
@@ -90,39 +217,9 @@ pub fn initialize() {
 
 /// Generate HTML with CSS classes for tokens
 fn generate_html_with_classes(code: &str, tokens: &[Token]) -> String {
-    let mut result = String::with_capacity(code.len() * 2);
-    let mut last_end = 0;
-
-    for token in tokens {
-        // Add any text between tokens
-        if token.start > last_end {
-            result.push_str(&escape_html(&code[last_end..token.start]));
-        }
-
-        // Add the token with its CSS class
-        let token_text = &code[token.start..token.end];
-        let css_class = get_css_class(&token.token_type);
-
-        if token.token_type != TokenType::Whitespace && !token_text.trim().is_empty() {
-            result.push_str(&format!(
-                r#"<span class="{}">{}</span>"#,
-                css_class,
-                escape_html(token_text)
-            ));
-        } else {
-            // Don't wrap whitespace in spans
-            result.push_str(&escape_html(token_text));
-        }
-
-        last_end = token.end;
-    }
-
-    // Add any remaining text
-    if last_end < code.len() {
-        result.push_str(&escape_html(&code[last_end..]));
-    }
-
-    result
+    let mut renderer = render::HtmlRenderer::new();
+    render::render_tokens(code, tokens, &mut renderer);
+    renderer.into_output()
 }
 
 /// Extract language from CSS class name (e.g., "language-c" -> Some("c"))
@@ -146,15 +243,6 @@ fn extract_language_from_class(class_name: &str) -> Option<String> {
     None
 }
 
-/// Escape HTML special characters
-fn escape_html(text: &str) -> String {
-    text.replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-        .replace('"', "&quot;")
-        .replace('\'', "&#x27;")
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -180,6 +268,68 @@ fi"#;
         assert!(result.contains("hl-string"));
     }
 
+    #[test]
+    fn test_highlight_code_tokens_json() {
+        let code = "int x;";
+        let result = highlight_code_tokens(code, Some("c".to_string()));
+        assert!(result.starts_with('['));
+        assert!(result.contains(r#""token_type":"Keyword""#));
+        assert!(result.contains(r#""css_class":"hl-keyword""#));
+    }
+
+    #[test]
+    fn test_highlight_code_themed_uses_inline_styles() {
+        let code = "int x;";
+        let result = highlight_code_themed(code, Some("c".to_string()), None);
+        assert!(result.contains("style="));
+        assert!(!result.contains("hl-"));
+    }
+
+    #[test]
+    fn test_highlight_code_themed_custom_theme() {
+        let code = "int x;";
+        let theme_json = r#"{"keyword": {"color": "#123456"}}"#;
+        let result =
+            highlight_code_themed(code, Some("c".to_string()), Some(theme_json.to_string()));
+        assert!(result.contains("#123456"));
+    }
+
+    #[test]
+    fn test_highlight_code_opts_rainbow() {
+        let code = "f(g(x))";
+        let result = highlight_code_opts(code, Some("c".to_string()), true);
+        assert!(result.contains("hl-bracket-0"));
+        assert!(result.contains("hl-bracket-1"));
+    }
+
+    #[test]
+    fn test_highlight_code_opts_rainbow_disabled_matches_plain() {
+        let code = "f(x)";
+        let result = highlight_code_opts(code, Some("c".to_string()), false);
+        assert_eq!(result, highlight_code(code, Some("c".to_string())));
+    }
+
+    #[test]
+    fn test_highlight_code_lines_wraps_and_emphasizes() {
+        let code = "int x;\nint y;\nint z;\n";
+        let result =
+            highlight_code_lines(code, Some("c".to_string()), Some("2".to_string()));
+        assert!(result.contains(r#"<span class="hl-line hl-line-emphasis" id="L2" data-line="2">"#));
+        assert!(result.contains(r#"<span class="hl-line" id="L1" data-line="1">"#));
+        assert!(result.contains("hl-keyword"));
+    }
+
+    #[test]
+    fn test_code_stats_counts_code_comments_and_blanks() {
+        let code = "int x = 1;\n\n// a comment\nint y = 2;\n";
+        let json = code_stats(code, Some("c".to_string()));
+
+        assert!(json.contains(r#""code":2"#));
+        assert!(json.contains(r#""comments":1"#));
+        assert!(json.contains(r#""blanks":1"#));
+        assert!(json.contains(r#""total":4"#));
+    }
+
     #[test]
     fn test_html_escaping() {
         let text = "<script>alert('xss')</script>";
@@ -502,6 +652,35 @@ int main() {
         assert!(result.contains("hl-comment")); // comments
     }
 
+    #[test]
+    fn test_generic_language_still_highlights() {
+        let code = "def greet(name):\n    # say hi\n    print(\"hi \" + name)\n";
+        let result = highlight_code(code, Some("python".to_string()));
+        assert!(result.contains("hl-string"));
+        assert!(result.contains("hl-comment"));
+        assert!(result.contains("hl-identifier"));
+    }
+
+    #[test]
+    fn test_node_generic_uses_double_slash_comments() {
+        let code = "// say hi\nconsole.log(\"hi\");\n";
+
+        let result = highlight_code(code, Some("node".to_string()));
+        assert!(result.contains(r#"<span class="hl-comment">// say hi</span>"#));
+
+        // Auto-detected via shebang, not an explicit "node" alias.
+        let shebang_code = "#!/usr/bin/env node\n// say hi\nconsole.log(\"hi\");\n";
+        let auto_result = highlight_code(shebang_code, None);
+        assert!(auto_result.contains(r#"<span class="hl-comment">// say hi</span>"#));
+    }
+
+    #[test]
+    fn test_python_generic_still_uses_hash_comments() {
+        let code = "# say hi\nprint(\"hi\")\n";
+        let result = highlight_code(code, Some("python".to_string()));
+        assert!(result.contains(r#"<span class="hl-comment"># say hi</span>"#));
+    }
+
     #[test]
     fn test_language_case_sensitivity() {
         let code = "int main() { return 0; }";
@@ -0,0 +1,209 @@
+/// Line-range gutter rendering: wraps each source line in a
+/// `<span class="hl-line[ hl-line-emphasis]" id="L{n}" data-line="{n}">`,
+/// for clickable line anchors and spotlighting specific lines in
+/// tutorials. Operates on the already-generated token spans, splitting any
+/// token whose text itself spans multiple lines (a block comment, a
+/// multi-line string) at each line boundary and re-opening a span with the
+/// same class on the next line, so highlighting never breaks mid-line.
+use std::ops::RangeInclusive;
+
+use crate::languages::get_css_class;
+use crate::render::escape_html;
+use crate::tokenizer::{Token, TokenType};
+
+/// Parse a highlight spec like `"3,7-9,20"` into 1-indexed line ranges.
+/// Malformed segments (non-numeric, `0`, or an inverted `end-start`) are
+/// skipped rather than failing the whole spec.
+fn parse_ranges(spec: &str) -> Vec<RangeInclusive<usize>> {
+    spec.split(',')
+        .filter_map(|part| {
+            let part = part.trim();
+            if part.is_empty() {
+                return None;
+            }
+
+            if let Some((start, end)) = part.split_once('-') {
+                let start: usize = start.trim().parse().ok()?;
+                let end: usize = end.trim().parse().ok()?;
+                (start > 0 && end >= start).then_some(start..=end)
+            } else {
+                let n: usize = part.parse().ok()?;
+                (n > 0).then_some(n..=n)
+            }
+        })
+        .collect()
+}
+
+fn is_highlighted(line: usize, ranges: &[RangeInclusive<usize>]) -> bool {
+    ranges.iter().any(|r| r.contains(&line))
+}
+
+/// Flatten tokens (plus any untokenized gaps) into `(css_class, text)`
+/// chunks covering all of `code`, in source order. `css_class` is `None`
+/// for whitespace/empty-trimmed spans, matching `generate_html_with_classes`.
+fn collect_chunks<'a>(code: &'a str, tokens: &[Token]) -> Vec<(Option<&'static str>, &'a str)> {
+    let mut chunks = Vec::new();
+    let mut last_end = 0;
+
+    for token in tokens {
+        if token.start > last_end {
+            chunks.push((None, &code[last_end..token.start]));
+        }
+
+        let text = &code[token.start..token.end];
+        if token.token_type != TokenType::Whitespace && !text.trim().is_empty() {
+            chunks.push((Some(get_css_class(&token.token_type)), text));
+        } else {
+            chunks.push((None, text));
+        }
+
+        last_end = token.end;
+    }
+
+    if last_end < code.len() {
+        chunks.push((None, &code[last_end..]));
+    }
+
+    chunks
+}
+
+fn push_line(output: &mut String, line_buf: &str, line_no: usize, ranges: &[RangeInclusive<usize>]) {
+    let emphasis = if is_highlighted(line_no, ranges) {
+        " hl-line-emphasis"
+    } else {
+        ""
+    };
+    output.push_str(&format!(
+        r#"<span class="hl-line{}" id="L{}" data-line="{}">{}</span>"#,
+        emphasis, line_no, line_no, line_buf
+    ));
+}
+
+/// Render `tokens` over `code` as one `hl-line` span per physical line,
+/// marking lines named by `highlight_spec` (a `"3,7-9,20"`-style string)
+/// with an extra `hl-line-emphasis` class.
+pub fn render_lines(code: &str, tokens: &[Token], highlight_spec: Option<&str>) -> String {
+    if code.is_empty() {
+        return String::new();
+    }
+
+    let ranges = highlight_spec.map(parse_ranges).unwrap_or_default();
+    let chunks = collect_chunks(code, tokens);
+
+    let mut output = String::with_capacity(code.len() * 2);
+    let mut line_buf = String::new();
+    let mut line_no = 1;
+
+    for (css_class, text) in chunks {
+        for (i, segment) in text.split('\n').enumerate() {
+            if i > 0 {
+                push_line(&mut output, &line_buf, line_no, &ranges);
+                output.push('\n');
+                line_buf.clear();
+                line_no += 1;
+            }
+
+            if segment.is_empty() {
+                continue;
+            }
+
+            match css_class {
+                Some(class) => line_buf.push_str(&format!(
+                    r#"<span class="{}">{}</span>"#,
+                    class,
+                    escape_html(segment)
+                )),
+                None => line_buf.push_str(&escape_html(segment)),
+            }
+        }
+    }
+
+    // A trailing newline ends the preceding line rather than starting a new
+    // (empty) one, matching `str::lines()` (and `stats::line_stats`, which
+    // counts lines the same way).
+    if !(line_buf.is_empty() && code.ends_with('\n') && line_no > 1) {
+        push_line(&mut output, &line_buf, line_no, &ranges);
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::{Language, Tokenizer};
+
+    fn tokenize(code: &str, language: Language) -> Vec<Token> {
+        Tokenizer::new(code, language).tokenize()
+    }
+
+    #[test]
+    fn test_each_line_gets_a_numbered_span() {
+        let code = "int x;\nint y;\n";
+        let tokens = tokenize(code, Language::C);
+        let html = render_lines(code, &tokens, None);
+
+        assert!(html.contains(r#"id="L1" data-line="1""#));
+        assert!(html.contains(r#"id="L2" data-line="2""#));
+        assert!(!html.contains(r#"id="L3""#));
+    }
+
+    #[test]
+    fn test_highlight_ranges_mark_emphasis_class() {
+        let code = "a;\nb;\nc;\nd;\n";
+        let tokens = tokenize(code, Language::C);
+        let html = render_lines(code, &tokens, Some("2,4"));
+
+        assert!(html.contains(r#"<span class="hl-line hl-line-emphasis" id="L2""#));
+        assert!(html.contains(r#"<span class="hl-line hl-line-emphasis" id="L4""#));
+        assert!(html.contains(r#"<span class="hl-line" id="L1""#));
+        assert!(html.contains(r#"<span class="hl-line" id="L3""#));
+    }
+
+    #[test]
+    fn test_range_spec_with_dash() {
+        let code = "a;\nb;\nc;\nd;\ne;\n";
+        let tokens = tokenize(code, Language::C);
+        let html = render_lines(code, &tokens, Some("2-4"));
+
+        assert!(html.contains(r#"id="L2" data-line="2""#));
+        assert!(html.contains("hl-line-emphasis"));
+        assert!(!html.contains(r#"<span class="hl-line hl-line-emphasis" id="L1""#));
+        assert!(!html.contains(r#"<span class="hl-line hl-line-emphasis" id="L5""#));
+    }
+
+    #[test]
+    fn test_multiline_block_comment_reopens_span_per_line() {
+        let code = "int a; /* start\nmiddle\nend */ int b;\n";
+        let tokens = tokenize(code, Language::C);
+        let html = render_lines(code, &tokens, None);
+
+        assert!(html.contains(r#"<span class="hl-comment">/* start</span>"#));
+        assert!(html.contains(r#"<span class="hl-comment">middle</span>"#));
+        assert!(html.contains(r#"<span class="hl-comment">end */</span>"#));
+    }
+
+    #[test]
+    fn test_trailing_newline_does_not_add_extra_line() {
+        let code = "int x;\n";
+        let tokens = tokenize(code, Language::C);
+        let html = render_lines(code, &tokens, None);
+
+        assert!(html.contains(r#"id="L1""#));
+        assert!(!html.contains(r#"id="L2""#));
+    }
+
+    #[test]
+    fn test_empty_code_renders_nothing() {
+        assert_eq!(render_lines("", &[], None), "");
+    }
+
+    #[test]
+    fn test_malformed_range_segments_are_ignored() {
+        let code = "a;\nb;\n";
+        let tokens = tokenize(code, Language::C);
+        let html = render_lines(code, &tokens, Some("x,0,2-1"));
+
+        assert!(!html.contains("hl-line-emphasis"));
+    }
+}
@@ -0,0 +1,176 @@
+/// Optional rainbow-bracket mode (as in rust-analyzer's
+/// `rainbow_highlighting`): matching `(){}[]` pairs get a CSS class derived
+/// from their nesting depth instead of the generic `hl-punctuation` class,
+/// one depth counter per bracket family so `(foo[bar(baz)])` colors parens
+/// and brackets independently. Gated behind `highlight_code_opts`'s
+/// `rainbow: bool`, so the plain `hl-punctuation` path is unaffected.
+use std::collections::HashMap;
+
+use crate::languages::get_css_class;
+use crate::render::escape_html;
+use crate::tokenizer::{Token, TokenType};
+
+/// Default number of distinct `hl-bracket-N` classes before the depth
+/// cycles back to `hl-bracket-0`.
+pub const DEFAULT_BRACKET_CYCLE: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum BracketFamily {
+    Paren,
+    Brace,
+    Bracket,
+}
+
+/// Classify a single-character punctuation token as a bracket, and whether
+/// it opens or closes its family.
+fn bracket_family(ch: char) -> Option<(BracketFamily, bool)> {
+    match ch {
+        '(' => Some((BracketFamily::Paren, true)),
+        ')' => Some((BracketFamily::Paren, false)),
+        '{' => Some((BracketFamily::Brace, true)),
+        '}' => Some((BracketFamily::Brace, false)),
+        '[' => Some((BracketFamily::Bracket, true)),
+        ']' => Some((BracketFamily::Bracket, false)),
+        _ => None,
+    }
+}
+
+/// Compute a rainbow CSS class override for each bracket token in `tokens`,
+/// keyed by the token's byte start offset (unique per token since tokens
+/// don't overlap). A stray closing bracket with nothing open in its family
+/// gets `hl-bracket-unmatched` instead of a depth class.
+pub fn rainbow_classes(code: &str, tokens: &[Token], cycle: usize) -> HashMap<usize, String> {
+    let cycle = cycle.max(1);
+    let mut classes = HashMap::new();
+    let mut depths: HashMap<BracketFamily, usize> = HashMap::new();
+
+    for token in tokens {
+        if token.token_type != TokenType::Punctuation {
+            continue;
+        }
+
+        let text = &code[token.start..token.end];
+        let mut chars = text.chars();
+        let (Some(ch), None) = (chars.next(), chars.next()) else {
+            continue;
+        };
+        let Some((family, is_open)) = bracket_family(ch) else {
+            continue;
+        };
+
+        let depth = depths.entry(family).or_insert(0);
+        if is_open {
+            classes.insert(token.start, format!("hl-bracket-{}", *depth % cycle));
+            *depth += 1;
+        } else if *depth == 0 {
+            classes.insert(token.start, "hl-bracket-unmatched".to_string());
+        } else {
+            *depth -= 1;
+            classes.insert(token.start, format!("hl-bracket-{}", *depth % cycle));
+        }
+    }
+
+    classes
+}
+
+/// Render `tokens` as HTML spans, substituting each bracket's rainbow class
+/// for its normal `hl-punctuation` class when `rainbow` is set. Per-token
+/// class overrides aren't expressible through the generic `Renderer`
+/// trait (which only sees a token's type and text, not its identity), so
+/// this renders directly rather than going through `render_tokens`.
+pub fn render_html(code: &str, tokens: &[Token], rainbow: bool, cycle: usize) -> String {
+    let overrides = if rainbow {
+        rainbow_classes(code, tokens, cycle)
+    } else {
+        HashMap::new()
+    };
+
+    let mut output = String::with_capacity(code.len() * 2);
+    let mut last_end = 0;
+
+    for token in tokens {
+        if token.start > last_end {
+            output.push_str(&escape_html(&code[last_end..token.start]));
+        }
+
+        let token_text = &code[token.start..token.end];
+        if token.token_type != TokenType::Whitespace && !token_text.trim().is_empty() {
+            let css_class = overrides
+                .get(&token.start)
+                .cloned()
+                .unwrap_or_else(|| get_css_class(&token.token_type).to_string());
+            output.push_str(&format!(
+                r#"<span class="{}">{}</span>"#,
+                css_class,
+                escape_html(token_text)
+            ));
+        } else {
+            output.push_str(&escape_html(token_text));
+        }
+
+        last_end = token.end;
+    }
+
+    if last_end < code.len() {
+        output.push_str(&escape_html(&code[last_end..]));
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::{Language, Tokenizer};
+
+    #[test]
+    fn test_nested_parens_get_increasing_depth_classes() {
+        let code = "f(g(h()))";
+        let tokens = Tokenizer::new(code, Language::C).tokenize();
+        let html = render_html(code, &tokens, true, DEFAULT_BRACKET_CYCLE);
+
+        assert!(html.contains(r#"<span class="hl-bracket-0">(</span>"#));
+        assert!(html.contains(r#"<span class="hl-bracket-1">(</span>"#));
+        assert!(html.contains(r#"<span class="hl-bracket-2">(</span>"#));
+    }
+
+    #[test]
+    fn test_depth_cycles_when_exceeding_n() {
+        let code = "((((((x))))))"; // 6 opens, cycle of 2
+        let tokens = Tokenizer::new(code, Language::C).tokenize();
+        let html = render_html(code, &tokens, true, 2);
+
+        assert!(html.contains(r#"<span class="hl-bracket-0">(</span>"#));
+        assert!(html.contains(r#"<span class="hl-bracket-1">(</span>"#));
+        assert!(!html.contains("hl-bracket-2"));
+    }
+
+    #[test]
+    fn test_unmatched_closing_bracket_is_flagged() {
+        let code = "x))";
+        let tokens = Tokenizer::new(code, Language::C).tokenize();
+        let html = render_html(code, &tokens, true, DEFAULT_BRACKET_CYCLE);
+
+        assert!(html.contains(r#"<span class="hl-bracket-unmatched">)</span>"#));
+    }
+
+    #[test]
+    fn test_bracket_families_are_independent() {
+        let code = "[a(b)]";
+        let tokens = Tokenizer::new(code, Language::C).tokenize();
+        let html = render_html(code, &tokens, true, DEFAULT_BRACKET_CYCLE);
+
+        assert!(html.contains(r#"<span class="hl-bracket-0">[</span>"#));
+        assert!(html.contains(r#"<span class="hl-bracket-0">(</span>"#));
+    }
+
+    #[test]
+    fn test_rainbow_disabled_keeps_plain_punctuation_class() {
+        let code = "f(x)";
+        let tokens = Tokenizer::new(code, Language::C).tokenize();
+        let html = render_html(code, &tokens, false, DEFAULT_BRACKET_CYCLE);
+
+        assert!(html.contains(r#"<span class="hl-punctuation">(</span>"#));
+        assert!(!html.contains("hl-bracket"));
+    }
+}
@@ -0,0 +1,178 @@
+/// Pluggable render backends, modeled on orgize's handler/render split: a
+/// single `render_tokens` drives token iteration and calls back into a
+/// `Renderer` implementation, so the tokenizer/injection pipeline is shared
+/// across every output format instead of each one re-walking the token list.
+use serde::Serialize;
+
+use crate::languages::get_css_class;
+use crate::tokenizer::{Token, TokenType};
+
+/// A render backend for a token stream. `enter_token` is called for every
+/// non-whitespace, non-empty token; everything else (whitespace, and any
+/// gaps between tokens) goes through `raw_text` instead, exactly as
+/// `generate_html_with_classes` used to inline that distinction.
+pub trait Renderer {
+    fn enter_token(&mut self, token_type: &TokenType, text: &str);
+    fn raw_text(&mut self, text: &str);
+}
+
+/// Walk `tokens` over `code`, calling back into `renderer` for each token
+/// and for the raw text between tokens.
+pub fn render_tokens<R: Renderer>(code: &str, tokens: &[Token], renderer: &mut R) {
+    let mut last_end = 0;
+
+    for token in tokens {
+        if token.start > last_end {
+            renderer.raw_text(&code[last_end..token.start]);
+        }
+
+        let token_text = &code[token.start..token.end];
+        if token.token_type != TokenType::Whitespace && !token_text.trim().is_empty() {
+            renderer.enter_token(&token.token_type, token_text);
+        } else {
+            renderer.raw_text(token_text);
+        }
+
+        last_end = token.end;
+    }
+
+    if last_end < code.len() {
+        renderer.raw_text(&code[last_end..]);
+    }
+}
+
+/// Escape HTML special characters.
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#x27;")
+}
+
+/// Renders a token stream as HTML spans (`<span class="hl-...">`), the
+/// original `highlight_code` output format.
+#[derive(Debug, Default)]
+pub struct HtmlRenderer {
+    output: String,
+}
+
+impl HtmlRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn into_output(self) -> String {
+        self.output
+    }
+}
+
+impl Renderer for HtmlRenderer {
+    fn enter_token(&mut self, token_type: &TokenType, text: &str) {
+        let css_class = get_css_class(token_type);
+        self.output.push_str(&format!(
+            r#"<span class="{}">{}</span>"#,
+            css_class,
+            escape_html(text)
+        ));
+    }
+
+    fn raw_text(&mut self, text: &str) {
+        self.output.push_str(&escape_html(text));
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct TokenSpan {
+    start: usize,
+    end: usize,
+    token_type: String,
+    css_class: String,
+}
+
+/// Renders a token stream as a JSON array of `{start, end, token_type,
+/// css_class}` spans (byte offsets into the original source), so a JS
+/// caller can build its own DOM or diff against a previous render instead
+/// of reparsing rendered HTML.
+#[derive(Debug, Default)]
+pub struct JsonRenderer {
+    spans: Vec<TokenSpan>,
+    position: usize,
+}
+
+impl JsonRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn into_json(self) -> String {
+        serde_json::to_string(&self.spans).unwrap_or_else(|_| "[]".to_string())
+    }
+}
+
+impl Renderer for JsonRenderer {
+    fn enter_token(&mut self, token_type: &TokenType, text: &str) {
+        let start = self.position;
+        let end = start + text.len();
+        self.spans.push(TokenSpan {
+            start,
+            end,
+            token_type: format!("{:?}", token_type),
+            css_class: get_css_class(token_type).to_string(),
+        });
+        self.position = end;
+    }
+
+    fn raw_text(&mut self, text: &str) {
+        self.position += text.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::{Language, Tokenizer};
+
+    #[test]
+    fn test_html_renderer_matches_span_format() {
+        let code = "int x;";
+        let tokens = Tokenizer::new(code, Language::C).tokenize();
+
+        let mut renderer = HtmlRenderer::new();
+        render_tokens(code, &tokens, &mut renderer);
+        let html = renderer.into_output();
+
+        assert!(html.contains(r#"<span class="hl-keyword">int</span>"#));
+        assert!(html.contains(r#"<span class="hl-identifier">x</span>"#));
+    }
+
+    #[test]
+    fn test_json_renderer_spans_cover_tokens_in_order() {
+        let code = "int x;";
+        let tokens = Tokenizer::new(code, Language::C).tokenize();
+
+        let mut renderer = JsonRenderer::new();
+        render_tokens(code, &tokens, &mut renderer);
+        let json = renderer.into_json();
+
+        assert!(json.contains(r#""token_type":"Keyword""#));
+        assert!(json.contains(r#""css_class":"hl-keyword""#));
+        assert!(json.starts_with('['));
+
+        let keyword_at = json.find(r#"{"start":0,"end":3,"token_type":"Keyword""#).expect("int span");
+        let ident_at = json.find(r#"{"start":4,"end":5,"token_type":"Identifier""#).expect("x span");
+        assert!(keyword_at < ident_at);
+    }
+
+    #[test]
+    fn test_json_renderer_skips_whitespace_spans() {
+        let code = "int  x;";
+        let tokens = Tokenizer::new(code, Language::C).tokenize();
+
+        let mut renderer = JsonRenderer::new();
+        render_tokens(code, &tokens, &mut renderer);
+        let json = renderer.into_json();
+
+        assert!(!json.contains("Whitespace"));
+    }
+}
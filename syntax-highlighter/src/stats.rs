@@ -0,0 +1,165 @@
+/// Per-language line statistics (tokei-style code/comment/blank counts),
+/// built on top of the existing tokenizers so the classification always
+/// matches what's actually displayed.
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::tokenizer::{Language, Token, TokenType, Tokenizer};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct LanguageStats {
+    pub code: usize,
+    pub comments: usize,
+    pub blanks: usize,
+    pub total: usize,
+}
+
+impl LanguageStats {
+    fn merge(&mut self, other: &LanguageStats) {
+        self.code += other.code;
+        self.comments += other.comments;
+        self.blanks += other.blanks;
+        self.total += other.total;
+    }
+}
+
+fn line_index_at(code: &str, byte_pos: usize) -> usize {
+    code[..byte_pos].matches('\n').count()
+}
+
+/// Classify each physical line of `code` as code, comment-only, or blank by
+/// walking `tokens`. A line is blank if every token on it is `Whitespace`, a
+/// comment line if its only non-whitespace tokens are `Comment`, and code
+/// otherwise. A multi-line block comment marks every spanned line as
+/// comment, except the opening line if code precedes the comment there.
+pub fn line_stats(code: &str, tokens: &[Token]) -> LanguageStats {
+    if code.is_empty() {
+        return LanguageStats::default();
+    }
+
+    let line_count = code.lines().count().max(1);
+    let mut has_code = vec![false; line_count];
+    let mut has_comment = vec![false; line_count];
+
+    for token in tokens {
+        if token.token_type == TokenType::Whitespace {
+            continue;
+        }
+
+        let text = &code[token.start..token.end];
+        let mut line_idx = line_index_at(code, token.start);
+
+        for (i, segment) in text.split('\n').enumerate() {
+            if i > 0 {
+                line_idx += 1;
+            }
+            if segment.trim().is_empty() && token.token_type != TokenType::Comment {
+                continue;
+            }
+            if line_idx >= line_count {
+                break;
+            }
+            if token.token_type == TokenType::Comment {
+                has_comment[line_idx] = true;
+            } else {
+                has_code[line_idx] = true;
+            }
+        }
+    }
+
+    let mut stats = LanguageStats {
+        total: line_count,
+        ..LanguageStats::default()
+    };
+
+    for i in 0..line_count {
+        if has_code[i] {
+            stats.code += 1;
+        } else if has_comment[i] {
+            stats.comments += 1;
+        } else {
+            stats.blanks += 1;
+        }
+    }
+
+    stats
+}
+
+/// Tokenize `code` as `language` and compute its line statistics.
+pub fn stats_for(code: &str, language: Language) -> LanguageStats {
+    let mut tokenizer = Tokenizer::new(code, language);
+    let tokens = tokenizer.tokenize();
+    line_stats(code, &tokens)
+}
+
+/// Aggregate stats across multiple (language, code) snippets — e.g. every
+/// embedded code block on a page — keyed by language.
+pub fn aggregate_stats<'a, I>(snippets: I) -> HashMap<Language, LanguageStats>
+where
+    I: IntoIterator<Item = (Language, &'a str)>,
+{
+    let mut totals: HashMap<Language, LanguageStats> = HashMap::new();
+    for (language, code) in snippets {
+        let stats = stats_for(code, language);
+        totals.entry(language).or_default().merge(&stats);
+    }
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blank_and_code_lines() {
+        let code = "int x = 1;\n\nint y = 2;\n";
+        let stats = stats_for(code, Language::C);
+        assert_eq!(stats.code, 2);
+        assert_eq!(stats.blanks, 1);
+        assert_eq!(stats.comments, 0);
+        assert_eq!(stats.total, 3);
+    }
+
+    #[test]
+    fn test_line_comment() {
+        let code = "int x = 1; // set x\n// just a comment\n";
+        let stats = stats_for(code, Language::C);
+        assert_eq!(stats.code, 1);
+        assert_eq!(stats.comments, 1);
+    }
+
+    #[test]
+    fn test_block_comment_spans_multiple_lines() {
+        let code = "int a; /* start\nmiddle\nend */ int b;\n";
+        let stats = stats_for(code, Language::C);
+        // Line 0 has code ("int a;") before the comment opens.
+        // Line 1 ("middle") is comment-only.
+        // Line 2 has code ("int b;") after the comment closes.
+        assert_eq!(stats.code, 2);
+        assert_eq!(stats.comments, 1);
+    }
+
+    #[test]
+    fn test_aggregate_across_snippets() {
+        let snippets = vec![
+            (Language::C, "int x;\n"),
+            (Language::C, "int y;\n\n"),
+            (Language::Bash, "echo hi\n"),
+        ];
+        let totals = aggregate_stats(snippets);
+
+        assert_eq!(totals[&Language::C].code, 2);
+        assert_eq!(totals[&Language::C].blanks, 1);
+        assert_eq!(totals[&Language::Bash].code, 1);
+    }
+
+    #[test]
+    fn test_stats_serialize_to_json() {
+        let stats = stats_for("int x;\n\n", Language::C);
+        let json = serde_json::to_string(&stats).unwrap();
+
+        assert!(json.contains(r#""code":1"#));
+        assert!(json.contains(r#""blanks":1"#));
+    }
+}
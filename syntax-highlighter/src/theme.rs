@@ -0,0 +1,258 @@
+/// Scope-based theming, inspired by Helix: each `TokenType` ("scope") maps
+/// to a `ScopeStyle` instead of a fixed `hl-*` CSS class, so a caller can
+/// render fully standalone HTML (inline `style="..."`) with no external
+/// stylesheet, or load a theme from JSON at runtime.
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::languages::get_css_class;
+use crate::render::{escape_html, Renderer};
+use crate::tokenizer::TokenType;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScopeStyle {
+    pub color: String,
+    #[serde(default)]
+    pub bold: bool,
+    #[serde(default)]
+    pub italic: bool,
+}
+
+impl ScopeStyle {
+    fn new(color: &str) -> Self {
+        Self {
+            color: color.to_string(),
+            bold: false,
+            italic: false,
+        }
+    }
+
+    fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    fn italic(mut self) -> Self {
+        self.italic = true;
+        self
+    }
+
+    fn to_css(&self) -> String {
+        let mut decls = vec![format!("color:{}", sanitize_css_color(&self.color))];
+        if self.bold {
+            decls.push("font-weight:bold".to_string());
+        }
+        if self.italic {
+            decls.push("font-style:italic".to_string());
+        }
+        decls.join(";")
+    }
+}
+
+/// `color` comes from a `ScopeStyle`, which may be deserialized straight
+/// from caller-supplied theme JSON, and gets interpolated unescaped into a
+/// `style="..."` attribute (escaping it like token text would turn a
+/// legitimate `"#fff"` into a broken declaration). Instead, only pass
+/// through characters a CSS color value can legitimately contain --
+/// anything else (such as a `"` that would close the attribute early)
+/// falls back to `"inherit"`.
+fn sanitize_css_color(color: &str) -> &str {
+    let is_safe = !color.is_empty()
+        && color
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '#' | '(' | ')' | ',' | '.' | '%' | '-' | ' '));
+
+    if is_safe {
+        color
+    } else {
+        "inherit"
+    }
+}
+
+/// The scope name for a token type, matching `get_css_class` minus its
+/// `hl-` prefix (e.g. `TokenType::Keyword` -> `"keyword"`), so a theme's
+/// JSON keys read the same as the class names they replace.
+fn scope_name(token_type: &TokenType) -> &'static str {
+    get_css_class(token_type)
+        .strip_prefix("hl-")
+        .unwrap_or("unknown")
+}
+
+/// A scope -> style mapping. Scopes without an explicit entry render
+/// unstyled (plain escaped text, no wrapping span).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Theme {
+    #[serde(flatten)]
+    scopes: HashMap<String, ScopeStyle>,
+}
+
+impl Theme {
+    pub fn get(&self, token_type: &TokenType) -> Option<&ScopeStyle> {
+        self.scopes.get(scope_name(token_type))
+    }
+
+    /// Parse a theme from a JSON object of `{"keyword": {"color": "#...",
+    /// "bold": true}, ...}` entries. Returns `None` on malformed JSON.
+    pub fn from_json(json: &str) -> Option<Theme> {
+        serde_json::from_str(json).ok()
+    }
+
+    /// Built-in dark theme (the default for `highlight_code_themed`).
+    pub fn dark() -> Theme {
+        let mut scopes = HashMap::new();
+        scopes.insert("keyword".to_string(), ScopeStyle::new("#c678dd").bold());
+        scopes.insert("identifier".to_string(), ScopeStyle::new("#e06c75"));
+        scopes.insert("function".to_string(), ScopeStyle::new("#61afef"));
+        scopes.insert("string".to_string(), ScopeStyle::new("#98c379"));
+        scopes.insert(
+            "string-interpolation".to_string(),
+            ScopeStyle::new("#56b6c2"),
+        );
+        scopes.insert("number".to_string(), ScopeStyle::new("#d19a66"));
+        scopes.insert("comment".to_string(), ScopeStyle::new("#5c6370").italic());
+        scopes.insert("operator".to_string(), ScopeStyle::new("#56b6c2"));
+        scopes.insert("punctuation".to_string(), ScopeStyle::new("#abb2bf"));
+        scopes.insert("key".to_string(), ScopeStyle::new("#e06c75"));
+        scopes.insert("value".to_string(), ScopeStyle::new("#98c379"));
+        scopes.insert("anchor".to_string(), ScopeStyle::new("#d19a66").bold());
+        scopes.insert("alias".to_string(), ScopeStyle::new("#d19a66"));
+        scopes.insert("tag".to_string(), ScopeStyle::new("#c678dd"));
+        Theme { scopes }
+    }
+
+    /// Built-in light theme.
+    pub fn light() -> Theme {
+        let mut scopes = HashMap::new();
+        scopes.insert("keyword".to_string(), ScopeStyle::new("#a626a4").bold());
+        scopes.insert("identifier".to_string(), ScopeStyle::new("#e45649"));
+        scopes.insert("function".to_string(), ScopeStyle::new("#4078f2"));
+        scopes.insert("string".to_string(), ScopeStyle::new("#50a14f"));
+        scopes.insert(
+            "string-interpolation".to_string(),
+            ScopeStyle::new("#0184bc"),
+        );
+        scopes.insert("number".to_string(), ScopeStyle::new("#986801"));
+        scopes.insert("comment".to_string(), ScopeStyle::new("#a0a1a7").italic());
+        scopes.insert("operator".to_string(), ScopeStyle::new("#0184bc"));
+        scopes.insert("punctuation".to_string(), ScopeStyle::new("#383a42"));
+        scopes.insert("key".to_string(), ScopeStyle::new("#e45649"));
+        scopes.insert("value".to_string(), ScopeStyle::new("#50a14f"));
+        scopes.insert("anchor".to_string(), ScopeStyle::new("#986801").bold());
+        scopes.insert("alias".to_string(), ScopeStyle::new("#986801"));
+        scopes.insert("tag".to_string(), ScopeStyle::new("#a626a4"));
+        Theme { scopes }
+    }
+}
+
+/// Renders a token stream as HTML spans with inline `style="..."`
+/// attributes resolved from a `Theme`, instead of `hl-*` CSS classes, so
+/// the output needs no external stylesheet.
+pub struct ThemedHtmlRenderer<'a> {
+    theme: &'a Theme,
+    output: String,
+}
+
+impl<'a> ThemedHtmlRenderer<'a> {
+    pub fn new(theme: &'a Theme) -> Self {
+        Self {
+            theme,
+            output: String::new(),
+        }
+    }
+
+    pub fn into_output(self) -> String {
+        self.output
+    }
+}
+
+impl<'a> Renderer for ThemedHtmlRenderer<'a> {
+    fn enter_token(&mut self, token_type: &TokenType, text: &str) {
+        match self.theme.get(token_type) {
+            Some(style) => {
+                self.output.push_str(&format!(
+                    r#"<span style="{}">{}</span>"#,
+                    style.to_css(),
+                    escape_html(text)
+                ));
+            }
+            None => self.output.push_str(&escape_html(text)),
+        }
+    }
+
+    fn raw_text(&mut self, text: &str) {
+        self.output.push_str(&escape_html(text));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::render_tokens;
+    use crate::tokenizer::{Language, Tokenizer};
+
+    #[test]
+    fn test_dark_theme_renders_inline_styles() {
+        let code = "int x;";
+        let tokens = Tokenizer::new(code, Language::C).tokenize();
+        let theme = Theme::dark();
+
+        let mut renderer = ThemedHtmlRenderer::new(&theme);
+        render_tokens(code, &tokens, &mut renderer);
+        let html = renderer.into_output();
+
+        assert!(html.contains(r#"style="color:#c678dd;font-weight:bold""#));
+        assert!(!html.contains("hl-"));
+    }
+
+    #[test]
+    fn test_theme_from_json_overrides_builtin() {
+        let json = r#"{"keyword": {"color": "#ff0000", "bold": true}}"#;
+        let theme = Theme::from_json(json).expect("valid theme json");
+
+        let style = theme.get(&TokenType::Keyword).expect("keyword scope set");
+        assert_eq!(style.color, "#ff0000");
+        assert!(style.bold);
+    }
+
+    #[test]
+    fn test_unstyled_scope_falls_back_to_plain_text() {
+        let theme = Theme::from_json(r#"{}"#).expect("valid empty theme json");
+        let code = "x";
+        let tokens = Tokenizer::new(code, Language::C).tokenize();
+
+        let mut renderer = ThemedHtmlRenderer::new(&theme);
+        render_tokens(code, &tokens, &mut renderer);
+        let html = renderer.into_output();
+
+        assert_eq!(html, "x");
+    }
+
+    #[test]
+    fn test_malformed_theme_json_returns_none() {
+        assert!(Theme::from_json("not json").is_none());
+    }
+
+    #[test]
+    fn test_theme_color_html_injection_is_sanitized() {
+        let json = r#"{"keyword": {"color": "#fff\"><script>alert(1)</script>"}}"#;
+        let theme = Theme::from_json(json).expect("valid theme json");
+        let code = "int";
+        let tokens = Tokenizer::new(code, Language::C).tokenize();
+
+        let mut renderer = ThemedHtmlRenderer::new(&theme);
+        render_tokens(code, &tokens, &mut renderer);
+        let html = renderer.into_output();
+
+        assert!(!html.contains("<script>"));
+        assert!(html.contains(r#"style="color:inherit""#));
+    }
+
+    #[test]
+    fn test_legitimate_color_values_pass_through_unsanitized() {
+        assert_eq!(sanitize_css_color("#fff"), "#fff");
+        assert_eq!(sanitize_css_color("#c678dd"), "#c678dd");
+        assert_eq!(sanitize_css_color("rgb(12, 34, 56)"), "rgb(12, 34, 56)");
+        assert_eq!(sanitize_css_color("darkred"), "darkred");
+    }
+}
@@ -10,6 +10,11 @@ pub enum TokenType {
 
     // Literals
     String,
+    /// The `$`/`{`/`(`/`}`/`)` delimiters of an interpolated expression
+    /// embedded in a string (e.g. `${n}` or `$(date)` inside a Bash
+    /// double-quoted string); the expression's own tokens keep their
+    /// normal `TokenType`.
+    StringInterpolation,
     Number,
     Comment,
 
@@ -17,6 +22,18 @@ pub enum TokenType {
     Operator,
     Punctuation,
 
+    // YAML structure
+    /// The token before the first unquoted `:` on a line in a mapping.
+    Key,
+    /// A scalar in value position that isn't a recognized literal.
+    Value,
+    /// `&name` anchor definition.
+    Anchor,
+    /// `*name` alias reference.
+    Alias,
+    /// `!!str` / `!Custom` type tag.
+    Tag,
+
     // Special
     Whitespace,
     Unknown,
@@ -29,20 +46,113 @@ pub struct Token {
     pub end: usize,
 }
 
+/// Lexer mode at a line boundary. This is the minimal state needed to know
+/// whether re-lexing a line in isolation would produce the same tokens as
+/// lexing the whole buffer up to that point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LexMode {
+    Normal,
+    InBlockComment,
+    InString { quote: char },
+}
+
+/// A single text edit, in byte offsets of the *old* buffer: `byte_start` is
+/// where the edit begins, `old_len` is how many old bytes it replaces, and
+/// `new_len` is how many bytes the replacement text is.
+#[derive(Debug, Clone, Copy)]
+pub struct Edit {
+    pub byte_start: usize,
+    pub old_len: usize,
+    pub new_len: usize,
+}
+
+/// Resumable tokenizer state captured after a full (or incremental) tokenize
+/// pass, so the next edit only needs to re-lex from the nearest unaffected
+/// line instead of the whole buffer.
+#[derive(Debug, Clone, Default)]
+pub struct TokenizerState {
+    /// Byte offset of the start of each line (line 0 first).
+    pub line_starts: Vec<usize>,
+    /// Lexer mode in effect at the start of each line, parallel to `line_starts`.
+    pub line_modes: Vec<LexMode>,
+}
+
+impl TokenizerState {
+    /// Derive per-line entry states by walking `tokens` and noting which
+    /// multi-line Comment/String spans each line boundary falls inside of.
+    pub fn from_tokens(input: &str, tokens: &[Token]) -> Self {
+        let mut line_starts = vec![0usize];
+        let mut line_modes = vec![LexMode::Normal];
+
+        for (offset, ch) in input.char_indices() {
+            if ch != '\n' {
+                continue;
+            }
+            let next_line_start = offset + 1;
+            if next_line_start >= input.len() {
+                break;
+            }
+            line_starts.push(next_line_start);
+            line_modes.push(mode_at(input, tokens, next_line_start));
+        }
+
+        Self {
+            line_starts,
+            line_modes,
+        }
+    }
+}
+
+/// The lexer mode active at `pos`, based on which token (if any) straddles it.
+fn mode_at(input: &str, tokens: &[Token], pos: usize) -> LexMode {
+    for token in tokens {
+        if token.start < pos && pos < token.end {
+            match token.token_type {
+                TokenType::Comment if input[token.start..].starts_with("/*") => {
+                    return LexMode::InBlockComment;
+                }
+                TokenType::String => {
+                    if let Some(quote) = input[token.start..].chars().next() {
+                        return LexMode::InString { quote };
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    LexMode::Normal
+}
+
 pub struct Tokenizer<'a> {
     input: &'a str,
     chars: std::str::Chars<'a>,
     position: usize, // Current byte position
     current_char: Option<char>,
     language: Language,
+    /// Tokens produced ahead of the current position (e.g. the pieces of an
+    /// interpolated string), drained by `next_token` before lexing more input.
+    pending: std::collections::VecDeque<Token>,
+    /// Byte offset of the first unquoted `:` on the current YAML line, if
+    /// any; tokens starting before it are mapping keys, tokens at or after
+    /// it are values. Recomputed at the first token of every line (after any
+    /// indentation).
+    yaml_colon_pos: Option<usize>,
+    /// Line-comment marker used by `Language::Generic`. Defaults to `#`
+    /// (Python, Ruby, Perl); override with `set_line_comment_prefix` for
+    /// interpreters that use something else (e.g. `//` for Node).
+    comment_prefix: &'static str,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Language {
     C,
     Bash,
     Makefile,
     Yaml,
+    /// A recognized-but-unimplemented language (e.g. Python, Node) detected
+    /// via shebang: no dedicated keyword table, but still strings, numbers,
+    /// comments, operators and identifiers instead of dropping to `Auto`.
+    Generic,
     Auto,
 }
 
@@ -57,9 +167,19 @@ impl<'a> Tokenizer<'a> {
             position: 0,
             current_char,
             language,
+            pending: std::collections::VecDeque::new(),
+            yaml_colon_pos: None,
+            comment_prefix: "#",
         }
     }
 
+    /// Override the line-comment marker used when `language` is
+    /// `Language::Generic` (default `#`). Accepts multi-character markers
+    /// (e.g. `//` for Node).
+    pub fn set_line_comment_prefix(&mut self, prefix: &'static str) {
+        self.comment_prefix = prefix;
+    }
+
     pub fn tokenize(&mut self) -> Vec<Token> {
         let mut tokens = Vec::new();
 
@@ -72,10 +192,153 @@ impl<'a> Tokenizer<'a> {
         tokens
     }
 
+    /// Like `tokenize`, but also returns the per-line entry state needed to
+    /// resume tokenizing later via `retokenize`.
+    pub fn tokenize_with_state(&mut self) -> (Vec<Token>, TokenizerState) {
+        let tokens = self.tokenize();
+        let state = TokenizerState::from_tokens(self.input, &tokens);
+        (tokens, state)
+    }
+
+    /// Re-tokenize `self.input` (the buffer *after* `edit` has been applied)
+    /// using `old_tokens`/`old_state` captured from the buffer *before* the
+    /// edit.
+    ///
+    /// It walks backward from the edit to the nearest preceding line whose
+    /// entry state was `LexMode::Normal` (a safe restart point) and re-lexes
+    /// from there to the end of the buffer. The fresh tokens are kept up to
+    /// the first line past the edit whose entry state reconverges with the
+    /// old one (shifted by the edit's length delta); after that the old
+    /// tokens are reused, shifted by the delta. A block comment or
+    /// unterminated string can change every following line's state, so that
+    /// line can lie well past the edited one. The re-lex itself is not cut
+    /// short at the reconvergence point: everything from the restart line to
+    /// the end of the buffer is lexed on every call.
+    pub fn retokenize(
+        &mut self,
+        old_tokens: &[Token],
+        old_state: &TokenizerState,
+        edit: Edit,
+    ) -> (Vec<Token>, TokenizerState) {
+        let delta = edit.new_len as isize - edit.old_len as isize;
+
+        // Find the last old line start at or before the edit whose entry
+        // state is Normal; re-lexing from there can't be contaminated by an
+        // unterminated comment/string that started even earlier.
+        let mut restart_old_line = 0;
+        for (i, &start) in old_state.line_starts.iter().enumerate() {
+            if start > edit.byte_start {
+                break;
+            }
+            if old_state.line_modes[i] == LexMode::Normal {
+                restart_old_line = i;
+            }
+        }
+        let restart_offset = old_state.line_starts[restart_old_line];
+        let edit_end_old = edit.byte_start + edit.old_len;
+
+        // Old lines entirely past the edit keep their original entry state,
+        // just shifted by `delta`; index them by their *new*-buffer start so
+        // we can recognize reconvergence as we re-lex forward.
+        let mut old_tail_modes_by_new_start: std::collections::HashMap<usize, LexMode> =
+            std::collections::HashMap::new();
+        for (i, &old_start) in old_state.line_starts.iter().enumerate() {
+            if old_start >= edit_end_old {
+                let new_start = (old_start as isize + delta) as usize;
+                old_tail_modes_by_new_start.insert(new_start, old_state.line_modes[i]);
+            }
+        }
+
+        let mut sub_tokenizer = Tokenizer::new(&self.input[restart_offset..], self.language);
+        // Shift to absolute offsets immediately: `mode_at` below (and the
+        // reconvergence scan) compares against `self.input`/`new_line_start`,
+        // which are both absolute, so the relexed tail's positions must be too.
+        let relexed: Vec<Token> = sub_tokenizer
+            .tokenize()
+            .into_iter()
+            .map(|t| Token {
+                token_type: t.token_type,
+                start: t.start + restart_offset,
+                end: t.end + restart_offset,
+            })
+            .collect();
+
+        // Find the first line past the edit whose freshly computed entry
+        // state matches what the old tail already had at the corresponding
+        // (shifted) position; from there on the old tokens, shifted by
+        // `delta`, are reused in place of the relexed ones.
+        let mut reconverge_at = None;
+        for (offset, ch) in self.input[restart_offset..].char_indices() {
+            if ch != '\n' {
+                continue;
+            }
+            let new_line_start = restart_offset + offset + 1;
+            if new_line_start >= self.input.len() || new_line_start < edit.byte_start + edit.new_len
+            {
+                continue;
+            }
+            if let Some(&old_mode) = old_tail_modes_by_new_start.get(&new_line_start) {
+                if mode_at(self.input, &relexed, new_line_start) == old_mode {
+                    reconverge_at = Some(new_line_start);
+                    break;
+                }
+            }
+        }
+
+        let mut tokens: Vec<Token> = old_tokens
+            .iter()
+            .filter(|t| t.end <= restart_offset)
+            .cloned()
+            .collect();
+
+        if let Some(cutoff) = reconverge_at {
+            // A relexed token may run past `cutoff` (e.g. a block comment the
+            // cutoff line sits inside); keep it whole and resume the old tail
+            // where it ends, so no bytes are left untokenized.
+            let mut resume_at = cutoff;
+            for token in &relexed {
+                if token.start >= cutoff {
+                    break;
+                }
+                resume_at = resume_at.max(token.end);
+                tokens.push(token.clone());
+            }
+
+            let resume_at_old = (resume_at as isize - delta) as usize;
+            for old_token in old_tokens {
+                if old_token.start >= resume_at_old {
+                    tokens.push(Token {
+                        token_type: old_token.token_type.clone(),
+                        start: (old_token.start as isize + delta) as usize,
+                        end: (old_token.end as isize + delta) as usize,
+                    });
+                }
+            }
+        } else {
+            tokens.extend(relexed);
+        }
+
+        let state = TokenizerState::from_tokens(self.input, &tokens);
+        (tokens, state)
+    }
+
     fn next_token(&mut self) -> Option<Token> {
+        if let Some(token) = self.pending.pop_front() {
+            return Some(token);
+        }
+
         let ch = self.current_char?;
         let start = self.position;
 
+        if self.language == Language::Yaml
+            && self.input[..start]
+                .rsplit('\n')
+                .next()
+                .is_some_and(|line_so_far| line_so_far.chars().all(|c| c == ' ' || c == '\t'))
+        {
+            self.yaml_colon_pos = self.compute_yaml_colon_pos();
+        }
+
         // Skip whitespace but track it for positions
         if ch.is_whitespace() {
             self.skip_whitespace();
@@ -91,8 +354,29 @@ impl<'a> Tokenizer<'a> {
             return self.read_comment(start);
         }
 
+        if self.language == Language::Yaml {
+            if let Some(token) = self.read_yaml_construct(start) {
+                return Some(token);
+            }
+        }
+
+        // Bash heredocs: `<<EOF` / `<<-EOF`
+        if self.language == Language::Bash && ch == '<' && self.peek_char() == Some('<') {
+            if let Some(token) = self.try_read_heredoc(start) {
+                return Some(token);
+            }
+        }
+
+        // C++ raw string literals: `R"delim(...)delim"`
+        if self.language == Language::C && ch == 'R' && self.peek_char() == Some('"') {
+            return self.read_raw_string(start);
+        }
+
         // Strings
         if ch == '"' || ch == '\'' {
+            if self.language == Language::Bash && ch == '"' {
+                return self.read_interpolated_string(start);
+            }
             return self.read_string(start, ch);
         }
 
@@ -151,6 +435,251 @@ impl<'a> Tokenizer<'a> {
         }
     }
 
+    fn is_at_line_start(&self) -> bool {
+        self.position == 0 || self.input.as_bytes().get(self.position - 1) == Some(&b'\n')
+    }
+
+    /// Byte offset (absolute) of the first unquoted, uncommented `:` on the
+    /// current line, scanning from `self.position` to the line's end, or
+    /// `None` if there isn't one.
+    fn compute_yaml_colon_pos(&self) -> Option<usize> {
+        let rest = &self.input[self.position..];
+        let mut in_single = false;
+        let mut in_double = false;
+
+        for (i, ch) in rest.char_indices() {
+            match ch {
+                '\n' => break,
+                '\'' if !in_double => in_single = !in_single,
+                '"' if !in_single => in_double = !in_double,
+                '#' if !in_single && !in_double => break,
+                ':' if !in_single && !in_double => return Some(self.position + i),
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    /// Recognize YAML-native constructs that aren't part of the generic
+    /// tokenizer: document markers (`---`/`...`), block scalar indicators
+    /// (`|`, `>` with chomping/indent suffixes), anchors (`&name`), aliases
+    /// (`*name`), and tags (`!!str`, `!Custom`). Returns `None` when the
+    /// current position doesn't start one of these, so the caller falls
+    /// through to the generic tokenizer.
+    fn read_yaml_construct(&mut self, start: usize) -> Option<Token> {
+        let ch = self.current_char?;
+
+        if self.is_at_line_start() {
+            let rest = &self.input[self.position..];
+            if rest == "---" || rest.starts_with("---\n") || rest.starts_with("--- ") {
+                self.advance();
+                self.advance();
+                self.advance();
+                return Some(Token {
+                    token_type: TokenType::Keyword,
+                    start,
+                    end: self.position,
+                });
+            }
+            if rest == "..." || rest.starts_with("...\n") || rest.starts_with("... ") {
+                self.advance();
+                self.advance();
+                self.advance();
+                return Some(Token {
+                    token_type: TokenType::Keyword,
+                    start,
+                    end: self.position,
+                });
+            }
+
+            // Document directives: `%YAML 1.1`, `%TAG !e! tag:example.com,2000:`.
+            if ch == '%' {
+                while let Some(c) = self.current_char {
+                    if c == '\n' {
+                        break;
+                    }
+                    self.advance();
+                }
+                return Some(Token {
+                    token_type: TokenType::Keyword,
+                    start,
+                    end: self.position,
+                });
+            }
+        }
+
+        if ch == '&' && self.peek_char().is_some_and(|c| c.is_alphanumeric() || c == '_') {
+            self.advance(); // '&'
+            while let Some(c) = self.current_char {
+                if c.is_alphanumeric() || c == '_' || c == '-' {
+                    self.advance();
+                } else {
+                    break;
+                }
+            }
+            return Some(Token {
+                token_type: TokenType::Anchor,
+                start,
+                end: self.position,
+            });
+        }
+
+        if ch == '*' && self.peek_char().is_some_and(|c| c.is_alphanumeric() || c == '_') {
+            self.advance(); // '*'
+            while let Some(c) = self.current_char {
+                if c.is_alphanumeric() || c == '_' || c == '-' {
+                    self.advance();
+                } else {
+                    break;
+                }
+            }
+            return Some(Token {
+                token_type: TokenType::Alias,
+                start,
+                end: self.position,
+            });
+        }
+
+        if ch == '!' {
+            self.advance(); // '!'
+            if self.current_char == Some('!') {
+                self.advance();
+            }
+            while let Some(c) = self.current_char {
+                if c.is_alphanumeric() || c == '_' || c == '-' || c == ':' || c == '/' || c == '.' {
+                    self.advance();
+                } else {
+                    break;
+                }
+            }
+            return Some(Token {
+                token_type: TokenType::Tag,
+                start,
+                end: self.position,
+            });
+        }
+
+        // YAML's `.inf`/`.nan` special floats: `read_identifier` never sees
+        // these (the leading `.`/`-`/`+` would otherwise be consumed by
+        // `read_operator`), and they're only meaningful as a value, not a
+        // key name, so this doesn't fire in key position (before the line's
+        // colon).
+        if matches!(ch, '.' | '-' | '+') && !self.is_key_position(start) {
+            let rest = &self.input[self.position..];
+            if let Some(literal) = crate::languages::yaml::SPECIAL_FLOATS.iter().find(|lit| {
+                rest.starts_with(**lit)
+                    && rest[lit.len()..]
+                        .chars()
+                        .next()
+                        .map_or(true, |c| !c.is_alphanumeric() && c != '_')
+            }) {
+                for _ in 0..literal.len() {
+                    self.advance();
+                }
+                return Some(Token {
+                    token_type: TokenType::Keyword,
+                    start,
+                    end: self.position,
+                });
+            }
+        }
+
+        if (ch == '|' || ch == '>') && !self.is_key_position(start) {
+            let header_indent = self.line_indent(start);
+
+            self.advance();
+            while let Some(c) = self.current_char {
+                if c == '-' || c == '+' || c.is_ascii_digit() {
+                    self.advance();
+                } else {
+                    break;
+                }
+            }
+            let indicator_end = self.position;
+
+            self.consume_block_scalar_body(header_indent);
+
+            return Some(Token {
+                token_type: TokenType::Operator,
+                start,
+                end: indicator_end,
+            });
+        }
+
+        None
+    }
+
+    fn is_key_position(&self, pos: usize) -> bool {
+        self.yaml_colon_pos.map(|colon| pos < colon).unwrap_or(false)
+    }
+
+    /// Indentation (count of leading spaces) of the line containing byte
+    /// offset `pos`.
+    fn line_indent(&self, pos: usize) -> usize {
+        let mut line_start = pos;
+        let bytes = self.input.as_bytes();
+        while line_start > 0 && bytes[line_start - 1] != b'\n' {
+            line_start -= 1;
+        }
+        self.input[line_start..]
+            .chars()
+            .take_while(|c| *c == ' ')
+            .count()
+    }
+
+    /// Consume a block scalar's body: every line more indented than
+    /// `header_indent` (or blank), starting right after the `|`/`>` header
+    /// line, is part of the literal/folded scalar and is queued as a single
+    /// `String` token instead of being re-parsed as keys/values.
+    fn consume_block_scalar_body(&mut self, header_indent: usize) {
+        // Consume the rest of the header line (e.g. a trailing comment).
+        while let Some(c) = self.current_char {
+            self.advance();
+            if c == '\n' {
+                break;
+            }
+        }
+
+        let body_start = self.position;
+
+        loop {
+            if self.current_char.is_none() {
+                break;
+            }
+
+            let line_start = self.position;
+            let mut line_end = line_start;
+            let bytes = self.input.as_bytes();
+            while line_end < bytes.len() && bytes[line_end] != b'\n' {
+                line_end += 1;
+            }
+            let line = &self.input[line_start..line_end];
+            let indent = line.chars().take_while(|c| *c == ' ').count();
+            let is_blank = line.trim().is_empty();
+
+            if !is_blank && indent <= header_indent {
+                break;
+            }
+
+            while self.position < line_end {
+                self.advance();
+            }
+            if self.current_char == Some('\n') {
+                self.advance();
+            }
+        }
+
+        let body_end = self.position;
+        if body_end > body_start {
+            self.pending.push_back(Token {
+                token_type: TokenType::String,
+                start: body_start,
+                end: body_end,
+            });
+        }
+    }
+
     fn is_comment_start(&self) -> bool {
         match self.language {
             Language::C => {
@@ -163,6 +692,7 @@ impl<'a> Tokenizer<'a> {
                 false
             }
             Language::Bash | Language::Makefile | Language::Yaml => self.current_char == Some('#'),
+            Language::Generic => self.input[self.position..].starts_with(self.comment_prefix),
             Language::Auto => {
                 // Try to detect comment style
                 self.current_char == Some('#')
@@ -199,7 +729,9 @@ impl<'a> Tokenizer<'a> {
                 }
             }
             _ => {
-                // Hash comments
+                // Line comment (`#` for Bash/Makefile/YAML/most Generic
+                // interpreters, or whatever `comment_prefix` was overridden
+                // to, e.g. `//` for Node) -- to end of line either way.
                 while let Some(ch) = self.current_char {
                     if ch == '\n' {
                         break;
@@ -241,6 +773,229 @@ impl<'a> Tokenizer<'a> {
         })
     }
 
+    /// Bash double-quoted string with `${...}`/`$(...)` interpolation:
+    /// literal text is emitted as `String`, each interpolation's delimiters
+    /// as `StringInterpolation`, and the bytes between them are re-tokenized
+    /// as Bash so e.g. `"count=${n}"` highlights `n` as an identifier rather
+    /// than swallowing the whole literal.
+    fn read_interpolated_string(&mut self, start: usize) -> Option<Token> {
+        self.advance(); // Skip opening quote
+        let mut literal_start = start;
+
+        loop {
+            match self.current_char {
+                None => break,
+                Some('"') => {
+                    self.advance();
+                    break;
+                }
+                Some('\\') => {
+                    self.advance();
+                    if self.current_char.is_some() {
+                        self.advance();
+                    }
+                }
+                Some('$') if matches!(self.peek_char(), Some('{') | Some('(')) => {
+                    if self.position > literal_start {
+                        self.pending.push_back(Token {
+                            token_type: TokenType::String,
+                            start: literal_start,
+                            end: self.position,
+                        });
+                    }
+
+                    let delim_start = self.position;
+                    self.advance(); // '$'
+                    let opener = self.current_char.unwrap_or('{');
+                    let closer = if opener == '{' { '}' } else { ')' };
+                    self.advance(); // '{' or '('
+                    self.pending.push_back(Token {
+                        token_type: TokenType::StringInterpolation,
+                        start: delim_start,
+                        end: self.position,
+                    });
+
+                    let inner_start = self.position;
+                    let mut depth = 1;
+                    while let Some(ch) = self.current_char {
+                        if ch == opener {
+                            depth += 1;
+                        } else if ch == closer {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        self.advance();
+                    }
+                    let inner_end = self.position;
+
+                    if inner_end > inner_start {
+                        let inner_src = &self.input[inner_start..inner_end];
+                        let mut inner_tokenizer = Tokenizer::new(inner_src, Language::Bash);
+                        for token in inner_tokenizer.tokenize() {
+                            self.pending.push_back(Token {
+                                token_type: token.token_type,
+                                start: token.start + inner_start,
+                                end: token.end + inner_start,
+                            });
+                        }
+                    }
+
+                    if self.current_char == Some(closer) {
+                        let close_start = self.position;
+                        self.advance();
+                        self.pending.push_back(Token {
+                            token_type: TokenType::StringInterpolation,
+                            start: close_start,
+                            end: self.position,
+                        });
+                    }
+
+                    literal_start = self.position;
+                }
+                Some(_) => self.advance(),
+            }
+        }
+
+        if self.position > literal_start {
+            self.pending.push_back(Token {
+                token_type: TokenType::String,
+                start: literal_start,
+                end: self.position,
+            });
+        }
+
+        self.pending.pop_front()
+    }
+
+    /// C++ raw string literal `R"delim(...)delim"`: consumed whole as a
+    /// single `String` token since its body must not be escape-processed.
+    fn read_raw_string(&mut self, start: usize) -> Option<Token> {
+        self.advance(); // 'R'
+        self.advance(); // opening '"'
+
+        let delim_start = self.position;
+        while let Some(ch) = self.current_char {
+            if ch == '(' {
+                break;
+            }
+            self.advance();
+        }
+        let delimiter = self.input[delim_start..self.position].to_string();
+        self.advance(); // '('
+
+        let closing = format!("){}\"", delimiter);
+        loop {
+            if self.current_char.is_none() {
+                break;
+            }
+            if self.input[self.position..].starts_with(closing.as_str()) {
+                for _ in 0..closing.len() {
+                    self.advance();
+                }
+                break;
+            }
+            self.advance();
+        }
+
+        Some(Token {
+            token_type: TokenType::String,
+            start,
+            end: self.position,
+        })
+    }
+
+    /// Bash heredoc (`<<EOF ... EOF`, or `<<-EOF` which allows the closing
+    /// delimiter to be indented with tabs): reads the delimiter word, then
+    /// consumes lines verbatim until one equals the delimiter (after
+    /// stripping leading tabs for the `<<-` form). Returns `None` (falling
+    /// back to normal operator handling) when `<<` isn't actually followed
+    /// by a delimiter word, e.g. a plain `<<` shift-like redirect chain.
+    fn try_read_heredoc(&mut self, start: usize) -> Option<Token> {
+        let rest = &self.input[self.position..];
+        let mut chars = rest.char_indices().peekable();
+        chars.next(); // first '<'
+        chars.next(); // second '<'
+
+        let mut idx = 2;
+        let mut strip_tabs = false;
+        if rest[idx..].starts_with('-') {
+            strip_tabs = true;
+            idx += 1;
+        }
+
+        let after_marker = &rest[idx..];
+        let trimmed = after_marker.trim_start_matches(' ');
+        idx += after_marker.len() - trimmed.len();
+
+        let quote = trimmed.chars().next().filter(|c| *c == '"' || *c == '\'');
+        let word_start = idx + quote.map(|_| 1).unwrap_or(0);
+        let word_rest = &rest[word_start..];
+        let word_end_offset = word_rest
+            .find(|c: char| {
+                if let Some(q) = quote {
+                    c == q
+                } else {
+                    c.is_whitespace() || c == ';' || c == '|' || c == '&'
+                }
+            })
+            .unwrap_or(word_rest.len());
+        let delimiter = &word_rest[..word_end_offset];
+
+        if delimiter.is_empty() {
+            return None;
+        }
+
+        let mut end_idx = word_start + word_end_offset;
+        if quote.is_some() && rest[end_idx..].starts_with(|c| quote == Some(c)) {
+            end_idx += 1;
+        }
+
+        // Advance past `<<[-]delimiter`.
+        for _ in 0..end_idx {
+            self.advance();
+        }
+
+        // Consume the rest of the current line (the command the heredoc is
+        // attached to), then the heredoc body itself.
+        while let Some(ch) = self.current_char {
+            self.advance();
+            if ch == '\n' {
+                break;
+            }
+        }
+
+        loop {
+            let line_start = self.position;
+            while let Some(ch) = self.current_char {
+                if ch == '\n' {
+                    break;
+                }
+                self.advance();
+            }
+            let mut line = &self.input[line_start..self.position];
+            if strip_tabs {
+                line = line.trim_start_matches('\t');
+            }
+            let is_delimiter_line = line == delimiter;
+
+            if self.current_char == Some('\n') {
+                self.advance();
+            }
+
+            if is_delimiter_line || self.current_char.is_none() {
+                break;
+            }
+        }
+
+        Some(Token {
+            token_type: TokenType::String,
+            start,
+            end: self.position,
+        })
+    }
+
     fn read_number(&mut self, start: usize) -> Option<Token> {
         while let Some(ch) = self.current_char {
             if ch.is_ascii_digit() || ch == '.' || ch == 'x' || ch == 'X' {
@@ -268,7 +1023,19 @@ impl<'a> Tokenizer<'a> {
 
         // Extract text safely using string slicing
         let text = &self.input[start..self.position];
-        let token_type = if self.is_keyword(text) {
+        let token_type = if self.language == Language::Yaml {
+            let in_key_position = self
+                .yaml_colon_pos
+                .map(|colon| start < colon)
+                .unwrap_or(false);
+            if in_key_position {
+                TokenType::Key
+            } else if self.is_keyword(text) {
+                TokenType::Keyword
+            } else {
+                TokenType::Value
+            }
+        } else if self.is_keyword(text) {
             TokenType::Keyword
         } else if self.is_function_call() {
             TokenType::Function
@@ -327,6 +1094,9 @@ impl<'a> Tokenizer<'a> {
             Language::Bash => BashLanguage::is_keyword(text),
             Language::Makefile => MakefileLanguage::is_keyword(text),
             Language::Yaml => YamlLanguage::is_keyword(text),
+            // No dedicated keyword table: Generic is a best-effort fallback
+            // for languages we don't have a highlighter for yet.
+            Language::Generic => false,
             Language::Auto => {
                 CLanguage::is_keyword(text)
                     || BashLanguage::is_keyword(text)
@@ -373,3 +1143,113 @@ impl<'a> Tokenizer<'a> {
         matches!(ch, '(' | ')' | '{' | '}' | '[' | ']' | ';')
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Summarize tokens as `(type, start, end)` triples for comparison,
+    /// since `Token` doesn't derive `PartialEq` (its positions are only
+    /// meaningful alongside a type).
+    fn shape(tokens: &[Token]) -> Vec<(TokenType, usize, usize)> {
+        tokens
+            .iter()
+            .map(|t| (t.token_type.clone(), t.start, t.end))
+            .collect()
+    }
+
+    /// Assert `retokenize` after `edit` (applied to `old_code` to produce
+    /// `new_code`) agrees exactly with a full fresh tokenize of `new_code`.
+    fn assert_retokenize_matches_full(old_code: &str, new_code: &str, edit: Edit) {
+        let (old_tokens, old_state) = Tokenizer::new(old_code, Language::C).tokenize_with_state();
+
+        let mut incremental = Tokenizer::new(new_code, Language::C);
+        let (patched_tokens, _) = incremental.retokenize(&old_tokens, &old_state, edit);
+
+        let full_tokens = Tokenizer::new(new_code, Language::C).tokenize();
+
+        assert_eq!(
+            shape(&patched_tokens),
+            shape(&full_tokens),
+            "retokenize({:?}) on {:?} diverged from a full tokenize of {:?}",
+            edit,
+            old_code,
+            new_code
+        );
+    }
+
+    #[test]
+    fn test_retokenize_reconverges_when_edit_opens_unterminated_string() {
+        // No closing `"` anywhere after the inserted one, so the string
+        // (and therefore `LexMode::InString`) swallows the rest of the
+        // buffer, changing every following line's mode from `Normal`.
+        let old_code = "int a;\nint b;\nint c;\n";
+        let new_code = "int a;\nint \"b;\nint c;\n";
+
+        let edit = Edit {
+            byte_start: "int a;\nint ".len(),
+            old_len: 0,
+            new_len: 1,
+        };
+
+        assert_retokenize_matches_full(old_code, new_code, edit);
+    }
+
+    #[test]
+    fn test_retokenize_reconverges_when_edit_opens_block_comment() {
+        // No closing `*/` anywhere after the inserted `/*`, so every line
+        // from here on enters `LexMode::InBlockComment`.
+        let old_code = "int a;\nint b;\nint c;\n";
+        let new_code = "int a;\n/* int b;\nint c;\n";
+
+        let edit = Edit {
+            byte_start: "int a;\n".len(),
+            old_len: 0,
+            new_len: "/* ".len(),
+        };
+
+        assert_retokenize_matches_full(old_code, new_code, edit);
+    }
+
+    #[test]
+    fn test_retokenize_reconverges_when_edit_closes_block_comment() {
+        // `old_code`'s block comment is unterminated, so every line after it
+        // opens is `InBlockComment`; closing it mid-buffer flips every
+        // following line back to `Normal`.
+        let old_code = "int a;\n/* comment\nint b;\nint c;\n";
+        let new_code = "int a;\n/* comment */\nint b;\nint c;\n";
+
+        let edit = Edit {
+            byte_start: "int a;\n/* comment".len(),
+            old_len: 0,
+            new_len: " */".len(),
+        };
+
+        assert_retokenize_matches_full(old_code, new_code, edit);
+    }
+
+    #[test]
+    fn test_yaml_special_floats_are_keywords_in_value_position() {
+        let tokens = Tokenizer::new("budget: .inf\nrate: -.Inf\nfudge: .NaN\n", Language::Yaml)
+            .tokenize();
+        let keywords: Vec<&str> = tokens
+            .iter()
+            .filter(|t| t.token_type == TokenType::Keyword)
+            .map(|t| &"budget: .inf\nrate: -.Inf\nfudge: .NaN\n"[t.start..t.end])
+            .collect();
+
+        assert_eq!(keywords, vec![".inf", "-.Inf", ".NaN"]);
+    }
+
+    #[test]
+    fn test_yaml_special_float_followed_by_more_text_is_not_truncated_keyword() {
+        // `.infinity` isn't a YAML literal; the boundary check must not
+        // mistake its `.inf` prefix for the real thing.
+        let code = "value: .infinity\n";
+        let tokens = Tokenizer::new(code, Language::Yaml).tokenize();
+
+        assert!(!tokens
+            .iter()
+            .any(|t| t.token_type == TokenType::Keyword && &code[t.start..t.end] == ".inf"));
+    }
+}